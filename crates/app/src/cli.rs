@@ -0,0 +1,203 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use engine::{GameMode, LaunchOptions};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "A snake game built on Bevy")]
+pub struct Args {
+    /// Grid width in cells.
+    #[arg(long, requires = "height")]
+    pub width: Option<i32>,
+
+    /// Grid height in cells.
+    #[arg(long, requires = "width")]
+    pub height: Option<i32>,
+
+    /// Seconds between snake steps.
+    #[arg(long, value_name = "SECONDS")]
+    pub tick: Option<f32>,
+
+    /// Seed for food placement; the same seed and inputs replay the same game.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Game mode. Skips the main menu.
+    #[arg(long, value_enum)]
+    pub mode: Option<ModeArg>,
+
+    /// Level file to play. Skips the main menu.
+    #[arg(long, value_name = "FILE")]
+    pub level: Option<PathBuf>,
+
+    /// Replay file to play back (binary, or JSON with a `.json` extension).
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["level", "mode", "seed"])]
+    pub replay: Option<PathBuf>,
+
+    /// Window size in pixels, e.g. `1280x720`.
+    #[arg(long, value_name = "WxH", value_parser = parse_size)]
+    pub window: Option<(u32, u32)>,
+
+    /// Start in fullscreen.
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Play with the built-in bot without opening a window, then print the
+    /// final score. With `--replay`, verify the replay instead.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Stop a headless run after this many ticks; a replay still running by
+    /// then fails.
+    #[arg(long, default_value_t = 10_000, requires = "headless")]
+    pub max_ticks: u64,
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone)]
+pub enum ModeArg {
+    Normal,
+    Practice,
+    Hard,
+}
+
+impl From<ModeArg> for GameMode {
+    fn from(mode: ModeArg) -> Self {
+        match mode {
+            ModeArg::Normal => Self::Normal,
+            ModeArg::Practice => Self::Practice,
+            ModeArg::Hard => Self::Hard,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Level {
+        path: PathBuf,
+        source: game::LevelError,
+    },
+    Replay {
+        path: PathBuf,
+        source: game::ReplayError,
+    },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Level { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Replay { path, source } => write!(f, "{}: {source}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl Args {
+    pub fn launch_options(&self) -> Result<LaunchOptions, CliError> {
+        let level = match &self.level {
+            Some(path) => {
+                let text = fs::read_to_string(path).map_err(|source| CliError::Read {
+                    path: path.clone(),
+                    source,
+                })?;
+                let level = game::Level::parse(&text).map_err(|source| CliError::Level {
+                    path: path.clone(),
+                    source,
+                })?;
+                Some(level)
+            }
+            None => None,
+        };
+        let replay = match &self.replay {
+            Some(path) => Some(read_replay(path)?),
+            None => None,
+        };
+
+        Ok(LaunchOptions {
+            grid_size: self.width.zip(self.height),
+            tick_seconds: self.tick,
+            window_size: self.window,
+            fullscreen: self.fullscreen,
+            seed: self.seed,
+            mode: self.mode.map(GameMode::from),
+            level,
+            level_path: self.level.clone(),
+            replay,
+        })
+    }
+}
+
+fn read_replay(path: &Path) -> Result<game::Replay, CliError> {
+    let bytes = fs::read(path).map_err(|source| CliError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let replay = if path.extension().is_some_and(|ext| ext == "json") {
+        game::Replay::from_json(&String::from_utf8_lossy(&bytes))
+    } else {
+        game::Replay::from_bytes(&bytes)
+    };
+    replay.map_err(|source| CliError::Replay {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parse_size(text: &str) -> Result<(u32, u32), String> {
+    let (width, height) = text
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {text:?}"))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<u32>()
+            .map_err(|err| format!("{value:?}: {err}"))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_map_to_launch_options() {
+        let args = Args::try_parse_from([
+            "beavy-snake-game",
+            "--width",
+            "30",
+            "--height",
+            "20",
+            "--seed",
+            "42",
+            "--mode",
+            "hard",
+            "--window",
+            "1280x720",
+        ])
+        .expect("valid arguments");
+
+        let options = args.launch_options().expect("no files to read");
+
+        assert_eq!(options.grid_size, Some((30, 20)));
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.mode, Some(GameMode::Hard));
+        assert_eq!(options.window_size, Some((1280, 720)));
+    }
+
+    #[test]
+    fn invalid_flags_are_rejected() {
+        assert!(Args::try_parse_from(["beavy-snake-game", "--width", "30"]).is_err());
+        assert!(Args::try_parse_from(["beavy-snake-game", "--window", "big"]).is_err());
+        assert!(Args::try_parse_from(["beavy-snake-game", "--max-ticks", "5"]).is_err());
+    }
+}
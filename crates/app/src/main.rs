@@ -1,3 +1,44 @@
-fn main() {
-    engine::run();
+mod cli;
+
+use std::error::Error;
+use std::process::ExitCode;
+
+use clap::Parser;
+use engine::LaunchOptions;
+
+fn main() -> ExitCode {
+    let args = cli::Args::parse();
+    let result = args
+        .launch_options()
+        .map_err(Box::from)
+        .and_then(|options| {
+            if args.headless {
+                run_headless(&options, args.max_ticks)
+            } else {
+                engine::run_with(options).map_err(Box::from)
+            }
+        });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Plays a game with the bot, or verifies a replay, and prints the final score.
+fn run_headless(options: &LaunchOptions, max_ticks: u64) -> Result<(), Box<dyn Error>> {
+    let state = match &options.replay {
+        Some(replay) => replay.play_limited(max_ticks)?,
+        None => {
+            let config = options.load_config()?;
+            let mut state = options.new_game(&config, options.mode.unwrap_or_default());
+            game::play_bot(&mut state, max_ticks);
+            state
+        }
+    };
+    println!("{}", state.score);
+    Ok(())
 }
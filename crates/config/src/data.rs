@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ConfigError;
+
+/// Something read from a `<id>.toml` data file, such as a theme or locale.
+pub(crate) trait DataFile: Sized {
+    fn id(&self) -> &str;
+    fn parse_at(id: &str, path: PathBuf, text: &str) -> Result<Self, ConfigError>;
+}
+
+/// Adds every `*.toml` file in `dir` to `items`, replacing an item with the
+/// same id. Files that cannot be read or parsed are returned as errors.
+pub(crate) fn load_dir<T: DataFile>(dir: &Path, mut items: Vec<T>) -> (Vec<T>, Vec<ConfigError>) {
+    let mut errors = Vec::new();
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(source) => {
+            errors.push(ConfigError::Io {
+                path: dir.to_path_buf(),
+                source,
+            });
+            Vec::new()
+        }
+    };
+    paths.sort();
+
+    for path in paths {
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let item = fs::read_to_string(&path)
+            .map_err(|source| ConfigError::Io {
+                path: path.clone(),
+                source,
+            })
+            .and_then(|text| T::parse_at(id, path.clone(), &text));
+        match item {
+            Ok(item) => match items.iter_mut().find(|known| known.id() == item.id()) {
+                Some(known) => *known = item,
+                None => items.push(item),
+            },
+            Err(err) => errors.push(err),
+        }
+    }
+    (items, errors)
+}
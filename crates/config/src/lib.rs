@@ -1,21 +1,129 @@
+mod data;
+mod load;
+pub mod locales;
+mod runtime;
+pub mod themes;
+
+pub use load::{config_path, load, load_from, parse, save, save_to, ConfigError, FieldError};
+pub use runtime::{
+    AudioConfig, ColorConfig, Config, ControlsConfig, DisplayConfig, GameplayConfig, GridConfig,
+    Rgb, TextConfig, TimingConfig, UiConfig, WindowConfig, WindowMode,
+};
+
 pub mod window {
     pub const TITLE: &str = "Beavy Snake Game";
     pub const WIDTH: u32 = 960;
     pub const HEIGHT: u32 = 540;
 }
 
+pub mod storage {
+    pub const DATA_DIR: &str = "beavy-snake-game";
+    pub const SAVE_FILE: &str = "savegame.json";
+    pub const CONFIG_FILE: &str = "config.toml";
+    pub const THEMES_DIR: &str = "themes";
+    pub const LOCALES_DIR: &str = "locales";
+    /// Under the assets folder.
+    pub const SKINS_DIR: &str = "skins";
+}
+
 pub mod grid {
     pub const WIDTH: i32 = 20;
     pub const HEIGHT: i32 = 15;
     pub const CELL_SIZE: f32 = 24.0;
     pub const SNAKE_SIZE: f32 = CELL_SIZE - 2.0;
     pub const FOOD_SIZE: f32 = CELL_SIZE - 4.0;
+    /// On screen, in pixels; the board itself is scaled to fit the window.
     pub const BORDER_THICKNESS: f32 = 2.0;
+    /// Pixels kept clear around the board, so the HUD does not cover it.
+    pub const BOARD_MARGIN: f32 = 40.0;
     pub const BORDER_Z: f32 = 0.1;
+    pub const CRASH_Z: f32 = 0.2;
+    /// Width and height in pixels of each generated snake shape.
+    pub const SKIN_FRAME_PIXELS: u32 = 32;
+    pub const FAIR_FOOD_DISTANCE: i32 = 5;
+    /// Most cells across or down a board; replays store cells as `u16`.
+    pub const MAX_SIZE: i32 = 1024;
 }
 
 pub mod timing {
     pub const TICK_SECONDS: f32 = 0.18;
+    pub const REWIND_TICK_SECONDS: f32 = 0.06;
+    pub const REWIND_HISTORY_TICKS: usize = 300;
+    /// Practice rewinds as often as the player likes, but only this far back,
+    /// so the history stops growing over a long session.
+    pub const PRACTICE_REWIND_HISTORY_TICKS: usize = 20_000;
+    pub const HOT_RELOAD_POLL_SECONDS: f32 = 0.5;
+    /// Turns held back for the coming ticks, one applied per tick.
+    pub const INPUT_QUEUE_LENGTH: usize = 3;
+    /// How long the crash is shown before the game-over screen.
+    pub const DEATH_SECONDS: f32 = 1.2;
+    /// The range the configuration accepts for ticks, normal and rewinding.
+    pub const MIN_TICK_SECONDS: f32 = 0.01;
+    pub const MAX_TICK_SECONDS: f32 = 10.0;
+    pub const MAX_DEATH_SECONDS: f32 = 10.0;
+}
+
+pub mod display {
+    pub const THEME: &str = "classic";
+    pub const LANGUAGE: &str = "en";
+    pub const SMOOTH_MOVEMENT: bool = true;
+    /// Empty draws the built-in snake shapes.
+    pub const SKIN: &str = "";
+    pub const REDUCED_MOTION: bool = false;
+    pub const FOLLOW_CAMERA: bool = false;
+}
+
+/// The camera that follows the snake, and the minimap shown with it.
+pub mod camera {
+    /// Pixels across a cell while following; a board that fits the window at
+    /// this size is shown whole.
+    pub const FOLLOW_CELL_PIXELS: f32 = 24.0;
+    /// Share of the view either side of its centre the head can move in
+    /// before the camera goes after it.
+    pub const DEAD_ZONE: f32 = 0.2;
+    /// How quickly the camera closes on the head, per second.
+    pub const SMOOTHING: f32 = 5.0;
+    /// Pixels across the longer side of the minimap.
+    pub const MINIMAP_PIXELS: f32 = 160.0;
+    pub const MINIMAP_ALPHA: f32 = 0.8;
+}
+
+/// Particles, screen shake and score popups.
+pub mod effects {
+    pub const EAT_PARTICLES: usize = 10;
+    pub const PARTICLE_SIZE: f32 = 5.0;
+    pub const PARTICLE_SPEED: f32 = 140.0;
+    pub const PARTICLE_SECONDS: f32 = 0.5;
+    /// Slows particles down, as a fraction of their speed lost per second.
+    pub const PARTICLE_DRAG: f32 = 2.5;
+    pub const DEATH_PIECE_SPEED: f32 = 90.0;
+    /// Most a broken-off piece of the snake turns, in radians per second.
+    pub const DEATH_PIECE_SPIN: f32 = 6.0;
+    pub const SHAKE_SECONDS: f32 = 0.35;
+    pub const SHAKE_STRENGTH: f32 = 8.0;
+    pub const POPUP_SECONDS: f32 = 0.8;
+    pub const POPUP_RISE: f32 = 36.0;
+    pub const POPUP_FONT_SIZE: f32 = 18.0;
+    /// How long the dying snake shows each of its two colours.
+    pub const FLASH_SECONDS: f32 = 0.1;
+    /// Share of the dying phase the snake flashes whole before it breaks apart.
+    pub const SHATTER_AT: f32 = 0.5;
+    pub const PARTICLE_Z: f32 = 0.5;
+    pub const POPUP_Z: f32 = 0.6;
+}
+
+pub mod audio {
+    pub const VOLUME: f32 = 0.8;
+    pub const MUSIC_VOLUME: f32 = 0.5;
+    pub const MUTED: bool = false;
+    pub const VOLUME_STEP: f32 = 0.1;
+    /// Samples per second of the generated sounds.
+    pub const SAMPLE_RATE: u32 = 22_050;
+}
+
+pub mod presets {
+    pub const GRID_SIZES: [(i32, i32); 4] = [(15, 10), (20, 15), (30, 20), (38, 21)];
+    pub const TICK_SECONDS: [f32; 4] = [0.25, 0.18, 0.12, 0.08];
 }
 
 pub mod ui {
@@ -28,26 +136,107 @@ pub mod ui {
     pub const SCORE_FONT_SIZE: f32 = 18.0;
     pub const TITLE_FONT_SIZE: f32 = 42.0;
     pub const SUBTITLE_FONT_SIZE: f32 = 22.0;
+    pub const SETTING_LABEL_WIDTH: f32 = 260.0;
+    pub const SETTING_ARROW_WIDTH: f32 = 48.0;
+    pub const KEY_SLOT_WIDTH: f32 = 160.0;
+    pub const KEY_SLOT_HEIGHT: f32 = 36.0;
     pub const BUTTON_FONT_SIZE: f32 = 20.0;
     pub const FPS_TOP: f32 = 8.0;
     pub const FPS_LEFT: f32 = 8.0;
     pub const SCORE_TOP: f32 = 8.0;
     pub const SCORE_RIGHT: f32 = 8.0;
+    pub const RELOAD_ERROR_BOTTOM: f32 = 8.0;
+    pub const RELOAD_ERROR_PADDING: f32 = 8.0;
+    /// Keeps confirmation dialogs above the screen that opened them.
+    pub const MODAL_Z_INDEX: i32 = 5;
+    /// Draws development overlays above every screen.
+    pub const OVERLAY_Z_INDEX: i32 = 10;
+    /// Opacity of the game-over screen over the board left behind it.
+    pub const GAME_OVER_ALPHA: f32 = 0.8;
+}
+
+/// Default key bindings, named after Bevy's `KeyCode` variants.
+pub mod controls {
+    pub const TURN_UP: &[&str] = &["ArrowUp", "KeyW"];
+    pub const TURN_DOWN: &[&str] = &["ArrowDown", "KeyS"];
+    pub const TURN_LEFT: &[&str] = &["ArrowLeft", "KeyA"];
+    pub const TURN_RIGHT: &[&str] = &["ArrowRight", "KeyD"];
+    pub const PAUSE: &[&str] = &["Escape", "KeyP"];
+    pub const RESTART: &[&str] = &["KeyR"];
+    pub const REWIND: &[&str] = &["Backspace"];
+    /// Keys each action can hold.
+    pub const SLOTS: usize = 2;
+    /// How far the left stick must be pushed, from 0 to 1, to turn.
+    pub const STICK_DEADZONE: f32 = 0.5;
 }
 
 pub mod text {
     pub const MENU_TITLE: &str = "Beavy Snake Game";
+    pub const MENU_CONTINUE: &str = "Continue";
     pub const MENU_START: &str = "Start Game";
+    pub const MENU_PRACTICE: &str = "Practice";
+    pub const MENU_HARD: &str = "Hard Mode";
     pub const MENU_EXIT: &str = "Exit";
     pub const GAME_OVER_TITLE: &str = "Game Over";
     pub const GAME_OVER_RESTART: &str = "Play Again";
     pub const GAME_OVER_EXIT: &str = "Exit";
     pub const SCORE_LABEL: &str = "Score: ";
+    pub const ASSISTED_SUFFIX: &str = " (assisted)";
     pub const FPS_LABEL: &str = "FPS: ";
+    pub const MENU_SETTINGS: &str = "Settings";
+    pub const SETTINGS_TITLE: &str = "Settings";
+    pub const SETTINGS_GRID: &str = "Grid";
+    pub const SETTINGS_SPEED: &str = "Speed";
+    pub const SETTINGS_WRAP: &str = "Wrap walls";
+    pub const SETTINGS_THEME: &str = "Theme";
+    pub const SETTINGS_WINDOW: &str = "Window";
+    pub const SETTINGS_VOLUME: &str = "Volume";
+    pub const SETTINGS_BACK: &str = "Back";
+    pub const ON: &str = "On";
+    pub const OFF: &str = "Off";
+    pub const WINDOW_WINDOWED: &str = "Windowed";
+    pub const WINDOW_BORDERLESS: &str = "Borderless";
+    pub const WINDOW_FULLSCREEN: &str = "Fullscreen";
+    pub const SETTINGS_CONTROLS: &str = "Controls";
+    pub const CONTROLS_TITLE: &str = "Controls";
+    pub const CONTROLS_RESET: &str = "Reset";
+    pub const CONTROLS_PROMPT: &str = "Press a key";
+    pub const CONTROLS_UNBOUND: &str = "-";
+    pub const CONTROLS_CONFLICT: &str = "{key} is already bound to {action}";
+    pub const ACTION_TURN_UP: &str = "Turn up";
+    pub const ACTION_TURN_DOWN: &str = "Turn down";
+    pub const ACTION_TURN_LEFT: &str = "Turn left";
+    pub const ACTION_TURN_RIGHT: &str = "Turn right";
+    pub const ACTION_PAUSE: &str = "Pause";
+    pub const ACTION_RESTART: &str = "Restart";
+    pub const ACTION_REWIND: &str = "Rewind";
+    pub const SETTINGS_LANGUAGE: &str = "Language";
+    pub const SETTINGS_GRID_VALUE: &str = "{width} x {height}";
+    pub const SETTINGS_SPEED_VALUE: &str = "{ms} ms";
+    pub const SETTINGS_VOLUME_VALUE: &str = "{percent}%";
+    pub const NUMBER_GROUP_SEPARATOR: &str = ",";
+    pub const PAUSE_TITLE: &str = "Paused";
+    pub const PAUSE_RESUME: &str = "Resume";
+    pub const PAUSE_RESTART: &str = "Restart";
+    pub const PAUSE_SETTINGS: &str = "Settings";
+    pub const PAUSE_QUIT: &str = "Quit to Menu";
+    pub const GAME_OVER_MENU: &str = "Main Menu";
+    pub const CONFIRM_YES: &str = "Yes";
+    pub const CONFIRM_NO: &str = "No";
+    pub const CONFIRM_EXIT: &str = "Quit the game?";
+    pub const CONFIRM_RESTART: &str = "Abandon this run and start over?";
+    pub const CONFIRM_QUIT: &str = "Leave this run and return to the menu?";
+    pub const SETTINGS_SMOOTH: &str = "Smooth movement";
+    pub const SETTINGS_REDUCED_MOTION: &str = "Reduced motion";
+    pub const SETTINGS_MUSIC: &str = "Music";
+    pub const SETTINGS_MUTE: &str = "Mute";
+    pub const SETTINGS_FOLLOW_CAMERA: &str = "Follow camera";
+    pub const SCORE_POPUP: &str = "+{points}";
 }
 
 pub mod colors {
     pub const WHITE: (f32, f32, f32) = (1.0, 1.0, 1.0);
+    pub const BACKGROUND: (f32, f32, f32) = (0.17, 0.17, 0.18);
     pub const MENU_BG: (f32, f32, f32) = (0.05, 0.06, 0.08);
     pub const MENU_BUTTON_BG: (f32, f32, f32) = (0.1, 0.12, 0.16);
     pub const MENU_BUTTON_BORDER: (f32, f32, f32) = (0.18, 0.2, 0.25);
@@ -59,4 +248,8 @@ pub mod colors {
     pub const SNAKE: (f32, f32, f32) = (0.2, 0.9, 0.4);
     pub const FOOD: (f32, f32, f32) = (0.95, 0.3, 0.3);
     pub const BORDER: (f32, f32, f32) = (0.85, 0.85, 0.85);
+    pub const BUTTON_HOVER: (f32, f32, f32) = (0.16, 0.19, 0.25);
+    pub const BUTTON_PRESSED: (f32, f32, f32) = (0.24, 0.28, 0.36);
+    pub const FOCUS_BORDER: (f32, f32, f32) = (0.95, 0.85, 0.3);
+    pub const CRASH: (f32, f32, f32) = (1.0, 0.95, 0.5);
 }
@@ -0,0 +1,375 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::runtime::{ColorConfig, Config, Rgb};
+use crate::{controls, storage};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    Invalid {
+        path: PathBuf,
+        errors: Vec<FieldError>,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Parse { path, message } => write!(f, "{}: {message}", path.display()),
+            Self::Invalid { path, errors } => {
+                write!(f, "{}: invalid configuration", path.display())?;
+                for error in errors {
+                    write!(f, "\n  {error}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Location of the user's config file, e.g. `~/.config/beavy-snake-game/config.toml`.
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(storage::DATA_DIR).join(storage::CONFIG_FILE))
+}
+
+/// Loads the user's config file, falling back to the defaults when there is
+/// no config directory or the file does not exist.
+pub fn load() -> Result<Config, ConfigError> {
+    match config_path() {
+        Some(path) if path.is_file() => load_from(&path),
+        _ => Ok(Config::default()),
+    }
+}
+
+pub fn load_from(path: &Path) -> Result<Config, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse(&text).map_err(|err| match err {
+        ConfigError::Parse { message, .. } => ConfigError::Parse {
+            path: path.to_path_buf(),
+            message,
+        },
+        ConfigError::Invalid { errors, .. } => ConfigError::Invalid {
+            path: path.to_path_buf(),
+            errors,
+        },
+        other => other,
+    })
+}
+
+/// Writes `config` to the user's config file, creating its directory. Every
+/// section is written out, so the file reflects the values in use.
+pub fn save(config: &Config) -> Result<PathBuf, ConfigError> {
+    let path = config_path().ok_or_else(|| ConfigError::Io {
+        path: PathBuf::from(storage::CONFIG_FILE),
+        source: io::Error::new(io::ErrorKind::NotFound, "no config directory"),
+    })?;
+    save_to(config, &path)?;
+    Ok(path)
+}
+
+pub fn save_to(config: &Config, path: &Path) -> Result<(), ConfigError> {
+    let text = toml::to_string_pretty(config).map_err(|err| ConfigError::Parse {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+    let io_error = |source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, text).map_err(io_error)
+}
+
+pub fn parse(text: &str) -> Result<Config, ConfigError> {
+    let config: Config = toml::from_str(text).map_err(|err| ConfigError::Parse {
+        path: PathBuf::new(),
+        message: err.to_string(),
+    })?;
+    let errors = config.validate();
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(ConfigError::Invalid {
+            path: PathBuf::new(),
+            errors,
+        })
+    }
+}
+
+impl Config {
+    /// Returns every invalid value, keyed by its dotted field path.
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, field: &str, message: &str| {
+            if !ok {
+                errors.push(FieldError {
+                    field: field.to_string(),
+                    message: message.to_string(),
+                });
+            }
+        };
+
+        check(self.window.width > 0, "window.width", "must be positive");
+        check(self.window.height > 0, "window.height", "must be positive");
+
+        let grid = &self.grid;
+        let size = format!("must be between 1 and {}", crate::grid::MAX_SIZE);
+        check(
+            (1..=crate::grid::MAX_SIZE).contains(&grid.width),
+            "grid.width",
+            &size,
+        );
+        check(
+            (1..=crate::grid::MAX_SIZE).contains(&grid.height),
+            "grid.height",
+            &size,
+        );
+        check(grid.cell_size > 0.0, "grid.cell_size", "must be positive");
+        check(
+            grid.snake_size > 0.0 && grid.snake_size <= grid.cell_size,
+            "grid.snake_size",
+            "must be positive and no larger than grid.cell_size",
+        );
+        check(
+            grid.food_size > 0.0 && grid.food_size <= grid.cell_size,
+            "grid.food_size",
+            "must be positive and no larger than grid.cell_size",
+        );
+        check(
+            grid.border_thickness >= 0.0,
+            "grid.border_thickness",
+            "must not be negative",
+        );
+        check(
+            grid.fair_food_distance >= 0,
+            "grid.fair_food_distance",
+            "must not be negative",
+        );
+
+        // Ranges also rule out NaN and infinity, which Bevy's timers reject.
+        let timing = &self.timing;
+        let ticks = crate::timing::MIN_TICK_SECONDS..=crate::timing::MAX_TICK_SECONDS;
+        let tick_range = format!(
+            "must be between {} and {}",
+            crate::timing::MIN_TICK_SECONDS,
+            crate::timing::MAX_TICK_SECONDS
+        );
+        check(
+            ticks.contains(&timing.tick_seconds),
+            "timing.tick_seconds",
+            &tick_range,
+        );
+        check(
+            ticks.contains(&timing.rewind_tick_seconds),
+            "timing.rewind_tick_seconds",
+            &tick_range,
+        );
+        check(
+            (0.0..=crate::timing::MAX_DEATH_SECONDS).contains(&timing.death_seconds),
+            "timing.death_seconds",
+            &format!("must be between 0 and {}", crate::timing::MAX_DEATH_SECONDS),
+        );
+
+        check(
+            (0.0..=1.0).contains(&self.audio.volume),
+            "audio.volume",
+            "must be between 0.0 and 1.0",
+        );
+        check(
+            (0.0..=1.0).contains(&self.audio.music_volume),
+            "audio.music_volume",
+            "must be between 0.0 and 1.0",
+        );
+
+        let mut bound: Vec<(&str, &str)> = Vec::new();
+        for (action, keys) in self.controls.entries() {
+            let field = format!("controls.{action}");
+            check(
+                keys.len() <= controls::SLOTS,
+                &field,
+                &format!("at most {} keys per action", controls::SLOTS),
+            );
+            for key in keys {
+                if let Some((other, _)) = bound.iter().find(|(_, bound_key)| *bound_key == key) {
+                    check(false, &field, &format!("{key} is already bound to {other}"));
+                } else {
+                    bound.push((action, key));
+                }
+            }
+        }
+
+        let ui = &self.ui;
+        for (field, size) in [
+            ("ui.hud_font_size", ui.hud_font_size),
+            ("ui.score_font_size", ui.score_font_size),
+            ("ui.title_font_size", ui.title_font_size),
+            ("ui.subtitle_font_size", ui.subtitle_font_size),
+            ("ui.button_font_size", ui.button_font_size),
+            ("ui.button_width", ui.button_width),
+            ("ui.button_height", ui.button_height),
+        ] {
+            check(size > 0.0, field, "must be positive");
+        }
+
+        errors.extend(self.colors.validate("colors"));
+
+        errors
+    }
+}
+
+impl ColorConfig {
+    /// Out-of-range colours, reported under `section`.
+    pub(crate) fn validate(&self, section: &str) -> Vec<FieldError> {
+        self.entries()
+            .into_iter()
+            .filter(|(_, rgb)| !valid_rgb(*rgb))
+            .map(|(name, _)| FieldError {
+                field: format!("{section}.{name}"),
+                message: "components must be between 0.0 and 1.0".to_string(),
+            })
+            .collect()
+    }
+}
+
+fn valid_rgb(rgb: Rgb) -> bool {
+    [rgb.0, rgb.1, rgb.2]
+        .iter()
+        .all(|channel| (0.0..=1.0).contains(channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_file_uses_defaults() {
+        let config = parse("").expect("empty config is valid");
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn partial_file_overrides_only_given_keys() {
+        let config =
+            parse("[grid]\nwidth = 10\n\n[timing]\ntick_seconds = 0.1\n").expect("valid config");
+
+        assert_eq!(config.grid.width, 10);
+        assert_eq!(config.grid.height, crate::grid::HEIGHT);
+        assert_eq!(config.timing.tick_seconds, 0.1);
+    }
+
+    #[test]
+    fn invalid_values_report_field_paths() {
+        let err = parse("[grid]\nwidth = 0\nheight = -1\n\n[colors]\nsnake = [2.0, 0.0, 0.0]\n")
+            .expect_err("invalid config");
+
+        let ConfigError::Invalid { errors, .. } = err else {
+            panic!("expected validation errors, got {err}");
+        };
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert!(fields.contains(&"grid.width"));
+        assert!(fields.contains(&"grid.height"));
+        assert!(fields.contains(&"colors.snake"));
+    }
+
+    #[test]
+    fn timings_and_grid_sizes_must_be_in_range() {
+        for value in ["inf", "nan", "1e-12", "-0.5"] {
+            let text = format!(
+                "[timing]\ntick_seconds = {value}\nrewind_tick_seconds = {value}\n\
+                 death_seconds = {value}\n"
+            );
+            let Err(ConfigError::Invalid { errors, .. }) = parse(&text) else {
+                panic!("{value} should be rejected");
+            };
+            let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+            assert!(fields.contains(&"timing.tick_seconds"), "{value}");
+            assert!(fields.contains(&"timing.rewind_tick_seconds"), "{value}");
+            if value != "1e-12" {
+                assert!(fields.contains(&"timing.death_seconds"), "{value}");
+            }
+        }
+
+        let err = parse("[grid]\nwidth = 70000\n").expect_err("grid too wide");
+        assert!(err.to_string().contains("grid.width"));
+    }
+
+    #[test]
+    fn saved_config_round_trips() {
+        let mut config = Config::default();
+        config.grid.width = 30;
+        config.gameplay.wrap_walls = true;
+        config.display.theme = "dark".to_string();
+        config.display.window_mode = crate::WindowMode::Borderless;
+        config.display.smooth_movement = false;
+        config.audio.volume = 0.5;
+        config.audio.muted = true;
+        let path = std::env::temp_dir().join(format!(
+            "beavy-config-test-{}/config.toml",
+            std::process::id()
+        ));
+
+        save_to(&config, &path).expect("config saves");
+        let loaded = load_from(&path).expect("saved config loads");
+        let _ = fs::remove_dir_all(path.parent().expect("parent dir"));
+
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    fn conflicting_bindings_are_rejected() {
+        let err = parse("[controls]\npause = [\"KeyW\"]\n").expect_err("W already turns up");
+
+        let ConfigError::Invalid { errors, .. } = err else {
+            panic!("expected validation errors, got {err}");
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "controls.pause");
+        assert!(errors[0].message.contains("turn_up"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let err = parse("[grid]\nwidht = 10\n").expect_err("typo in key");
+
+        assert!(matches!(err, ConfigError::Parse { .. }));
+        assert!(err.to_string().contains("widht"));
+    }
+}
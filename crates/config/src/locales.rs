@@ -0,0 +1,135 @@
+//! Translations of the UI text.
+//!
+//! A locale is a TOML file with a display `name` and a `[text]` table using
+//! the same keys as the config file's `[text]` section. Keys a translation
+//! leaves out fall back to English. English itself comes from the constants
+//! in [`crate::text`]; other built-in locales are compiled in, and any
+//! `*.toml` file in the user's locales directory is added to them, replacing
+//! a built-in locale with the same file stem.
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::data::{self, DataFile};
+use crate::{storage, ConfigError, TextConfig};
+
+pub const ENGLISH: &str = "en";
+
+const ENGLISH_NAME: &str = "English";
+
+const BUILTIN: [(&str, &str); 3] = [
+    ("de", include_str!("../locales/de.toml")),
+    ("es", include_str!("../locales/es.toml")),
+    ("fr", include_str!("../locales/fr.toml")),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    /// File stem, as stored in `display.language`.
+    pub id: String,
+    pub name: String,
+    pub text: TextConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LocaleFile {
+    name: String,
+    #[serde(default)]
+    text: TextConfig,
+}
+
+/// Parses a locale file. `id` names the locale in settings and errors.
+pub fn parse(id: &str, text: &str) -> Result<Locale, ConfigError> {
+    Locale::parse_at(id, PathBuf::from(format!("{id}.toml")), text)
+}
+
+impl DataFile for Locale {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn parse_at(id: &str, path: PathBuf, text: &str) -> Result<Self, ConfigError> {
+        let file: LocaleFile = toml::from_str(text).map_err(|err| ConfigError::Parse {
+            path,
+            message: err.to_string(),
+        })?;
+        Ok(Self {
+            id: id.to_string(),
+            name: file.name,
+            text: file.text,
+        })
+    }
+}
+
+/// The locales shipped with the game, English first.
+pub fn builtin() -> Vec<Locale> {
+    let english = Locale {
+        id: ENGLISH.to_string(),
+        name: ENGLISH_NAME.to_string(),
+        text: TextConfig::default(),
+    };
+    std::iter::once(english)
+        .chain(
+            BUILTIN
+                .iter()
+                .map(|(id, text)| parse(id, text).expect("built-in locales are valid")),
+        )
+        .collect()
+}
+
+/// Directory searched for user locales, e.g. `~/.config/beavy-snake-game/locales`.
+pub fn locales_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(storage::DATA_DIR).join(storage::LOCALES_DIR))
+}
+
+/// The built-in locales followed by the user's, along with any user locale
+/// files that could not be read.
+pub fn load_all() -> (Vec<Locale>, Vec<ConfigError>) {
+    match locales_dir() {
+        Some(dir) if dir.is_dir() => data::load_dir(&dir, builtin()),
+        _ => (builtin(), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_keys_fall_back_to_english() {
+        let locale =
+            parse("xx", "name = \"Test\"\n[text]\nmenu_start = \"Go\"\n").expect("valid locale");
+
+        assert_eq!(locale.text.menu_start, "Go");
+        assert_eq!(locale.text.menu_exit, crate::text::MENU_EXIT);
+        assert!(parse("xx", "name = \"Test\"\n[text]\nmenu_strat = \"Go\"\n").is_err());
+    }
+
+    #[test]
+    fn builtin_locales_translate_every_key() {
+        let keys = |table: &toml::Table| {
+            let mut keys: Vec<String> = table.keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let english = toml::Table::try_from(TextConfig::default()).expect("text serializes");
+
+        for (id, text) in BUILTIN {
+            let file: toml::Table = toml::from_str(text).expect("locale parses");
+            let translated = file["text"].as_table().expect("text table");
+            assert_eq!(keys(translated), keys(&english), "{id}");
+        }
+    }
+
+    #[test]
+    fn numbers_use_the_locale_separator() {
+        let mut text = TextConfig::default();
+
+        assert_eq!(text.format_number(7), "7");
+        assert_eq!(text.format_number(1234567), "1,234,567");
+        text.number_group_separator = ".".to_string();
+        assert_eq!(text.format_number(100000), "100.000");
+    }
+}
@@ -0,0 +1,452 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{audio, colors, controls, display, grid, text, timing, ui, window};
+
+pub type Rgb = (f32, f32, f32);
+
+/// Values read from the user's config file. Every section defaults to the
+/// matching compile-time constants, so a file only needs the keys it changes.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub window: WindowConfig,
+    pub grid: GridConfig,
+    pub timing: TimingConfig,
+    pub gameplay: GameplayConfig,
+    pub display: DisplayConfig,
+    pub audio: AudioConfig,
+    pub controls: ControlsConfig,
+    pub ui: UiConfig,
+    pub colors: ColorConfig,
+    pub text: TextConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: window::TITLE.to_string(),
+            width: window::WIDTH,
+            height: window::HEIGHT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GridConfig {
+    pub width: i32,
+    pub height: i32,
+    pub cell_size: f32,
+    pub snake_size: f32,
+    pub food_size: f32,
+    pub border_thickness: f32,
+    pub fair_food_distance: i32,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            width: grid::WIDTH,
+            height: grid::HEIGHT,
+            cell_size: grid::CELL_SIZE,
+            snake_size: grid::SNAKE_SIZE,
+            food_size: grid::FOOD_SIZE,
+            border_thickness: grid::BORDER_THICKNESS,
+            fair_food_distance: grid::FAIR_FOOD_DISTANCE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimingConfig {
+    pub tick_seconds: f32,
+    pub rewind_tick_seconds: f32,
+    pub rewind_history_ticks: usize,
+    /// Time between the crash and the game-over screen.
+    pub death_seconds: f32,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            tick_seconds: timing::TICK_SECONDS,
+            rewind_tick_seconds: timing::REWIND_TICK_SECONDS,
+            rewind_history_ticks: timing::REWIND_HISTORY_TICKS,
+            death_seconds: timing::DEATH_SECONDS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameplayConfig {
+    pub wrap_walls: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    pub theme: String,
+    pub window_mode: WindowMode,
+    pub language: String,
+    /// Slide the snake between cells instead of moving it once per tick.
+    pub smooth_movement: bool,
+    /// Sprite sheet `<skin>.png` in the assets' skins folder, drawn in place
+    /// of the built-in snake shapes.
+    pub skin: String,
+    /// Leave out particles, screen shake and score popups.
+    pub reduced_motion: bool,
+    /// Follow the snake's head, with a minimap of the whole board, instead
+    /// of fitting the board to the window.
+    pub follow_camera: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            theme: display::THEME.to_string(),
+            window_mode: WindowMode::default(),
+            language: display::LANGUAGE.to_string(),
+            smooth_movement: display::SMOOTH_MOVEMENT,
+            skin: display::SKIN.to_string(),
+            reduced_motion: display::REDUCED_MOTION,
+            follow_camera: display::FOLLOW_CAMERA,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioConfig {
+    pub volume: f32,
+    /// Loudness of the music relative to the sound effects.
+    pub music_volume: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            volume: audio::VOLUME,
+            music_volume: audio::MUSIC_VOLUME,
+            muted: audio::MUTED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ControlsConfig {
+    pub turn_up: Vec<String>,
+    pub turn_down: Vec<String>,
+    pub turn_left: Vec<String>,
+    pub turn_right: Vec<String>,
+    pub pause: Vec<String>,
+    pub restart: Vec<String>,
+    pub rewind: Vec<String>,
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        let keys = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        Self {
+            turn_up: keys(controls::TURN_UP),
+            turn_down: keys(controls::TURN_DOWN),
+            turn_left: keys(controls::TURN_LEFT),
+            turn_right: keys(controls::TURN_RIGHT),
+            pause: keys(controls::PAUSE),
+            restart: keys(controls::RESTART),
+            rewind: keys(controls::REWIND),
+        }
+    }
+}
+
+impl ControlsConfig {
+    /// Every action's keys, keyed by the action's field name.
+    pub fn entries(&self) -> [(&'static str, &[String]); 7] {
+        [
+            ("turn_up", &self.turn_up),
+            ("turn_down", &self.turn_down),
+            ("turn_left", &self.turn_left),
+            ("turn_right", &self.turn_right),
+            ("pause", &self.pause),
+            ("restart", &self.restart),
+            ("rewind", &self.rewind),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    pub panel_gap: f32,
+    pub button_width: f32,
+    pub button_height: f32,
+    pub button_border: f32,
+    pub hud_font_size: f32,
+    pub score_font_size: f32,
+    pub title_font_size: f32,
+    pub subtitle_font_size: f32,
+    pub button_font_size: f32,
+    pub fps_top: f32,
+    pub fps_left: f32,
+    pub score_top: f32,
+    pub score_right: f32,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            panel_gap: ui::PANEL_GAP,
+            button_width: ui::BUTTON_WIDTH,
+            button_height: ui::BUTTON_HEIGHT,
+            button_border: ui::BUTTON_BORDER,
+            hud_font_size: ui::HUD_FONT_SIZE,
+            score_font_size: ui::SCORE_FONT_SIZE,
+            title_font_size: ui::TITLE_FONT_SIZE,
+            subtitle_font_size: ui::SUBTITLE_FONT_SIZE,
+            button_font_size: ui::BUTTON_FONT_SIZE,
+            fps_top: ui::FPS_TOP,
+            fps_left: ui::FPS_LEFT,
+            score_top: ui::SCORE_TOP,
+            score_right: ui::SCORE_RIGHT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorConfig {
+    pub white: Rgb,
+    pub background: Rgb,
+    pub menu_bg: Rgb,
+    pub menu_button_bg: Rgb,
+    pub menu_button_border: Rgb,
+    pub game_over_bg: Rgb,
+    pub game_over_text: Rgb,
+    pub game_over_button_bg: Rgb,
+    pub game_over_exit_bg: Rgb,
+    pub game_over_button_border: Rgb,
+    pub snake: Rgb,
+    pub food: Rgb,
+    pub border: Rgb,
+    pub button_hover: Rgb,
+    pub button_pressed: Rgb,
+    pub focus_border: Rgb,
+    pub crash: Rgb,
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        Self {
+            white: colors::WHITE,
+            background: colors::BACKGROUND,
+            menu_bg: colors::MENU_BG,
+            menu_button_bg: colors::MENU_BUTTON_BG,
+            menu_button_border: colors::MENU_BUTTON_BORDER,
+            game_over_bg: colors::GAME_OVER_BG,
+            game_over_text: colors::GAME_OVER_TEXT,
+            game_over_button_bg: colors::GAME_OVER_BUTTON_BG,
+            game_over_exit_bg: colors::GAME_OVER_EXIT_BG,
+            game_over_button_border: colors::GAME_OVER_BUTTON_BORDER,
+            snake: colors::SNAKE,
+            food: colors::FOOD,
+            border: colors::BORDER,
+            button_hover: colors::BUTTON_HOVER,
+            button_pressed: colors::BUTTON_PRESSED,
+            focus_border: colors::FOCUS_BORDER,
+            crash: colors::CRASH,
+        }
+    }
+}
+
+impl ColorConfig {
+    pub(crate) fn entries(&self) -> [(&'static str, Rgb); 17] {
+        [
+            ("white", self.white),
+            ("background", self.background),
+            ("menu_bg", self.menu_bg),
+            ("menu_button_bg", self.menu_button_bg),
+            ("menu_button_border", self.menu_button_border),
+            ("game_over_bg", self.game_over_bg),
+            ("game_over_text", self.game_over_text),
+            ("game_over_button_bg", self.game_over_button_bg),
+            ("game_over_exit_bg", self.game_over_exit_bg),
+            ("game_over_button_border", self.game_over_button_border),
+            ("snake", self.snake),
+            ("food", self.food),
+            ("border", self.border),
+            ("button_hover", self.button_hover),
+            ("button_pressed", self.button_pressed),
+            ("focus_border", self.focus_border),
+            ("crash", self.crash),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TextConfig {
+    pub menu_title: String,
+    pub menu_continue: String,
+    pub menu_start: String,
+    pub menu_practice: String,
+    pub menu_hard: String,
+    pub menu_exit: String,
+    pub game_over_title: String,
+    pub game_over_restart: String,
+    pub game_over_exit: String,
+    pub score_label: String,
+    pub assisted_suffix: String,
+    pub fps_label: String,
+    pub menu_settings: String,
+    pub settings_title: String,
+    pub settings_grid: String,
+    pub settings_speed: String,
+    pub settings_wrap: String,
+    pub settings_theme: String,
+    pub settings_window: String,
+    pub settings_volume: String,
+    pub settings_back: String,
+    pub on: String,
+    pub off: String,
+    pub window_windowed: String,
+    pub window_borderless: String,
+    pub window_fullscreen: String,
+    pub settings_controls: String,
+    pub controls_title: String,
+    pub controls_reset: String,
+    pub controls_prompt: String,
+    pub controls_unbound: String,
+    pub controls_conflict: String,
+    pub action_turn_up: String,
+    pub action_turn_down: String,
+    pub action_turn_left: String,
+    pub action_turn_right: String,
+    pub action_pause: String,
+    pub action_restart: String,
+    pub action_rewind: String,
+    pub settings_language: String,
+    pub settings_grid_value: String,
+    pub settings_speed_value: String,
+    pub settings_volume_value: String,
+    pub number_group_separator: String,
+    pub pause_title: String,
+    pub pause_resume: String,
+    pub pause_restart: String,
+    pub pause_settings: String,
+    pub pause_quit: String,
+    pub game_over_menu: String,
+    pub confirm_yes: String,
+    pub confirm_no: String,
+    pub confirm_exit: String,
+    pub confirm_restart: String,
+    pub confirm_quit: String,
+    pub settings_smooth: String,
+    pub settings_reduced_motion: String,
+    pub settings_music: String,
+    pub settings_mute: String,
+    pub settings_follow_camera: String,
+    pub score_popup: String,
+}
+
+impl Default for TextConfig {
+    fn default() -> Self {
+        Self {
+            menu_title: text::MENU_TITLE.to_string(),
+            menu_continue: text::MENU_CONTINUE.to_string(),
+            menu_start: text::MENU_START.to_string(),
+            menu_practice: text::MENU_PRACTICE.to_string(),
+            menu_hard: text::MENU_HARD.to_string(),
+            menu_exit: text::MENU_EXIT.to_string(),
+            game_over_title: text::GAME_OVER_TITLE.to_string(),
+            game_over_restart: text::GAME_OVER_RESTART.to_string(),
+            game_over_exit: text::GAME_OVER_EXIT.to_string(),
+            score_label: text::SCORE_LABEL.to_string(),
+            assisted_suffix: text::ASSISTED_SUFFIX.to_string(),
+            fps_label: text::FPS_LABEL.to_string(),
+            menu_settings: text::MENU_SETTINGS.to_string(),
+            settings_title: text::SETTINGS_TITLE.to_string(),
+            settings_grid: text::SETTINGS_GRID.to_string(),
+            settings_speed: text::SETTINGS_SPEED.to_string(),
+            settings_wrap: text::SETTINGS_WRAP.to_string(),
+            settings_theme: text::SETTINGS_THEME.to_string(),
+            settings_window: text::SETTINGS_WINDOW.to_string(),
+            settings_volume: text::SETTINGS_VOLUME.to_string(),
+            settings_back: text::SETTINGS_BACK.to_string(),
+            on: text::ON.to_string(),
+            off: text::OFF.to_string(),
+            window_windowed: text::WINDOW_WINDOWED.to_string(),
+            window_borderless: text::WINDOW_BORDERLESS.to_string(),
+            window_fullscreen: text::WINDOW_FULLSCREEN.to_string(),
+            settings_controls: text::SETTINGS_CONTROLS.to_string(),
+            controls_title: text::CONTROLS_TITLE.to_string(),
+            controls_reset: text::CONTROLS_RESET.to_string(),
+            controls_prompt: text::CONTROLS_PROMPT.to_string(),
+            controls_unbound: text::CONTROLS_UNBOUND.to_string(),
+            controls_conflict: text::CONTROLS_CONFLICT.to_string(),
+            action_turn_up: text::ACTION_TURN_UP.to_string(),
+            action_turn_down: text::ACTION_TURN_DOWN.to_string(),
+            action_turn_left: text::ACTION_TURN_LEFT.to_string(),
+            action_turn_right: text::ACTION_TURN_RIGHT.to_string(),
+            action_pause: text::ACTION_PAUSE.to_string(),
+            action_restart: text::ACTION_RESTART.to_string(),
+            action_rewind: text::ACTION_REWIND.to_string(),
+            settings_language: text::SETTINGS_LANGUAGE.to_string(),
+            settings_grid_value: text::SETTINGS_GRID_VALUE.to_string(),
+            settings_speed_value: text::SETTINGS_SPEED_VALUE.to_string(),
+            settings_volume_value: text::SETTINGS_VOLUME_VALUE.to_string(),
+            number_group_separator: text::NUMBER_GROUP_SEPARATOR.to_string(),
+            pause_title: text::PAUSE_TITLE.to_string(),
+            pause_resume: text::PAUSE_RESUME.to_string(),
+            pause_restart: text::PAUSE_RESTART.to_string(),
+            pause_settings: text::PAUSE_SETTINGS.to_string(),
+            pause_quit: text::PAUSE_QUIT.to_string(),
+            game_over_menu: text::GAME_OVER_MENU.to_string(),
+            confirm_yes: text::CONFIRM_YES.to_string(),
+            confirm_no: text::CONFIRM_NO.to_string(),
+            confirm_exit: text::CONFIRM_EXIT.to_string(),
+            confirm_restart: text::CONFIRM_RESTART.to_string(),
+            confirm_quit: text::CONFIRM_QUIT.to_string(),
+            settings_smooth: text::SETTINGS_SMOOTH.to_string(),
+            settings_reduced_motion: text::SETTINGS_REDUCED_MOTION.to_string(),
+            settings_music: text::SETTINGS_MUSIC.to_string(),
+            settings_mute: text::SETTINGS_MUTE.to_string(),
+            settings_follow_camera: text::SETTINGS_FOLLOW_CAMERA.to_string(),
+            score_popup: text::SCORE_POPUP.to_string(),
+        }
+    }
+}
+
+impl TextConfig {
+    /// `value` with digits grouped in threes, e.g. `12,345`.
+    pub fn format_number(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut formatted = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                formatted.push_str(&self.number_group_separator);
+            }
+            formatted.push(digit);
+        }
+        formatted
+    }
+}
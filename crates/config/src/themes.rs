@@ -0,0 +1,138 @@
+//! Colour themes.
+//!
+//! A theme is a TOML file with a display `name` and a `[colors]` table using
+//! the same keys as the config file's `[colors]` section; missing colours fall
+//! back to the classic palette. The built-in themes are compiled in, and any
+//! `*.toml` file in the user's themes directory is added to them, replacing a
+//! built-in theme with the same file stem.
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::data::{self, DataFile};
+use crate::{storage, ColorConfig, ConfigError};
+
+pub const CLASSIC: &str = "classic";
+
+const BUILTIN: [(&str, &str); 4] = [
+    (CLASSIC, include_str!("../themes/classic.toml")),
+    ("dark", include_str!("../themes/dark.toml")),
+    ("gameboy", include_str!("../themes/gameboy.toml")),
+    (
+        "high_contrast",
+        include_str!("../themes/high_contrast.toml"),
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// File stem, as stored in `display.theme`.
+    pub id: String,
+    pub name: String,
+    pub colors: ColorConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: String,
+    #[serde(default)]
+    colors: ColorConfig,
+}
+
+/// Parses a theme file. `id` names the theme in settings and errors.
+pub fn parse(id: &str, text: &str) -> Result<Theme, ConfigError> {
+    Theme::parse_at(id, PathBuf::from(format!("{id}.toml")), text)
+}
+
+impl DataFile for Theme {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn parse_at(id: &str, path: PathBuf, text: &str) -> Result<Self, ConfigError> {
+        let file: ThemeFile = toml::from_str(text).map_err(|err| ConfigError::Parse {
+            path: path.clone(),
+            message: err.to_string(),
+        })?;
+        let errors = file.colors.validate("colors");
+        if !errors.is_empty() {
+            return Err(ConfigError::Invalid { path, errors });
+        }
+        Ok(Self {
+            id: id.to_string(),
+            name: file.name,
+            colors: file.colors,
+        })
+    }
+}
+
+/// The themes shipped with the game, classic first.
+pub fn builtin() -> Vec<Theme> {
+    BUILTIN
+        .iter()
+        .map(|(id, text)| parse(id, text).expect("built-in themes are valid"))
+        .collect()
+}
+
+/// Directory searched for user themes, e.g. `~/.config/beavy-snake-game/themes`.
+pub fn themes_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(storage::DATA_DIR).join(storage::THEMES_DIR))
+}
+
+/// The built-in themes followed by the user's, along with any user theme
+/// files that could not be read.
+pub fn load_all() -> (Vec<Theme>, Vec<ConfigError>) {
+    match themes_dir() {
+        Some(dir) if dir.is_dir() => data::load_dir(&dir, builtin()),
+        _ => (builtin(), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn classic_file_matches_the_default_colors() {
+        let themes = builtin();
+
+        assert_eq!(themes[0].id, CLASSIC);
+        assert_eq!(themes[0].colors, ColorConfig::default());
+        assert_eq!(themes.len(), BUILTIN.len());
+    }
+
+    #[test]
+    fn user_themes_replace_and_extend_builtins() {
+        let dir = std::env::temp_dir().join(format!("beavy-themes-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp dir");
+        fs::write(dir.join("dark.toml"), "name = \"Darker\"\n").expect("write theme");
+        fs::write(
+            dir.join("sunset.toml"),
+            "name = \"Sunset\"\n[colors]\nsnake = [1.0, 0.5, 0.0]\n",
+        )
+        .expect("write theme");
+        fs::write(
+            dir.join("broken.toml"),
+            "name = \"Broken\"\n[colors]\nfood = [2.0, 0.0, 0.0]\n",
+        )
+        .expect("write theme");
+
+        let (themes, errors) = data::load_dir(&dir, builtin());
+        let _ = fs::remove_dir_all(&dir);
+
+        let ids: Vec<&str> = themes.iter().map(|theme| theme.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["classic", "dark", "gameboy", "high_contrast", "sunset"]
+        );
+        assert_eq!(themes[1].name, "Darker");
+        assert_eq!(themes[4].colors.snake, (1.0, 0.5, 0.0));
+        assert_eq!(themes[4].colors.food, ColorConfig::default().food);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("colors.food"));
+    }
+}
@@ -1,59 +1,263 @@
 use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
 use bevy::prelude::*;
+use bevy::ui::UiSystems;
 use beavy_config as config;
 
-use crate::gameover::{cleanup_game_over, game_over_input, setup_game_over};
-use crate::gameplay::{advance_game, cleanup_gameplay, handle_input, setup_gameplay};
+use crate::bindings::KeyBindings;
+use crate::camera::{fit_board, follow_head, reset_camera, CameraFocus};
+use crate::controls::{
+    controls_input, not_capturing, setup_controls, ControlsButtonAction, ControlsRoot, KeyCapture,
+};
+use crate::effects::{
+    clear_effects, shake_camera, shatter_snake, spawn_effects, update_particles, update_popups,
+    Shake,
+};
+use crate::focus::{navigate_buttons, style_buttons, Focus};
+use crate::gameover::{game_over_input, setup_game_over, GameOverAction, GameOverRoot};
+use crate::gamepads::{assign_gamepads, GamepadPlayers};
+use crate::gameplay::{
+    advance_game, cleanup_gameplay, glide_snake, handle_input, play_death, restart_game,
+    setup_gameplay, sync_board, GameEvent, ReplayPlayback,
+};
 use crate::hud::{cleanup_hud, setup_hud, update_fps_text};
-use crate::menu::{cleanup_menu, menu_input, setup_menu};
-use crate::state::AppState;
+use crate::launch::LaunchOptions;
+use crate::locale::Locales;
+use crate::menu::{menu_input, setup_menu, MenuButtonAction, MenuRoot};
+use crate::minimap::{draw_minimap, setup_minimap};
+use crate::modal::{exit_on_confirm, modal_input, no_modal, Confirmed, ModalButton, ModalRoot};
+use crate::pause::{
+    pause_input, pause_on_focus_lost, pause_on_gamepad_lost, resume_paused, setup_pause,
+    toggle_pause, PauseAction, PauseRoot,
+};
+use crate::rewind::{not_rewinding, rewind_game};
+use crate::save::{autosave_on_close, autosave_on_focus_lost, delete_save};
+use crate::settings::{
+    settings_input, setup_settings, window_mode, SettingsButtonAction, SettingsRoot,
+};
+use crate::skin::{load_skin, setup_skin};
+use crate::state::{AppState, ConfigResource, PauseState};
+use crate::theme::{apply_theme, Role, Theme, Themes};
+use crate::widgets::{despawn_screen, sync_sliders, sync_toggles, WidgetApp};
 
-pub fn run() {
-    App::new()
-        .add_plugins(
-            DefaultPlugins.set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: config::window::TITLE.to_string(),
-                    resolution: (config::window::WIDTH, config::window::HEIGHT).into(),
-                    ..default()
-                }),
-                ..default()
-            }),
-        )
-        .add_plugins(FrameTimeDiagnosticsPlugin::default())
-        .init_state::<AppState>()
-        .add_systems(Startup, setup_camera)
-        .add_systems(OnEnter(AppState::Menu), setup_menu)
-        .add_systems(Update, menu_input.run_if(in_state(AppState::Menu)))
-        .add_systems(OnExit(AppState::Menu), cleanup_menu)
-        .add_systems(
-            OnEnter(AppState::Playing),
-            (setup_hud, setup_gameplay),
-        )
-        .add_systems(
-            OnExit(AppState::Playing),
-            (cleanup_gameplay, cleanup_hud),
-        )
-        .add_systems(OnEnter(AppState::GameOver), setup_game_over)
-        .add_systems(
-            Update,
-            game_over_input.run_if(in_state(AppState::GameOver)),
-        )
-        .add_systems(OnExit(AppState::GameOver), cleanup_game_over)
-        .configure_sets(Update, PlayingSet.run_if(in_state(AppState::Playing)))
-        .add_systems(
-            Update,
-            handle_input.in_set(PlayingSet),
-        )
-        .add_systems(
-            Update,
-            advance_game.in_set(PlayingSet),
+/// Runs the game with the configuration files as they are. Fails before
+/// opening a window if the configuration is invalid.
+pub fn run() -> Result<(), config::ConfigError> {
+    run_with(LaunchOptions::default())
+}
+
+/// Runs the game with command-line overrides. Fails before opening a window
+/// if the overrides leave the configuration invalid.
+pub fn run_with(options: LaunchOptions) -> Result<(), config::ConfigError> {
+    let mut config = options.load_config()?;
+    let initial_state = if options.starts_game() {
+        AppState::Playing
+    } else {
+        AppState::Menu
+    };
+    let mode = options.mode.unwrap_or_default();
+    // Logging only starts with the app's plugins, so problems found before
+    // then are held back until it does.
+    let mut warnings = Vec::new();
+    let (bindings, unknown_keys) = KeyBindings::from_config(&config.controls);
+    for name in unknown_keys {
+        warnings.push(format!("controls: unknown key {name:?} ignored"));
+    }
+    let (themes, theme_errors) = config::themes::load_all();
+    for err in theme_errors {
+        warnings.push(format!("{err}\ntheme skipped"));
+    }
+    let themes = Themes(themes);
+    if themes.get(&config.display.theme).is_none() {
+        warnings.push(format!(
+            "display.theme: unknown theme {:?}, using {}",
+            config.display.theme,
+            config::themes::CLASSIC
+        ));
+    }
+    let theme = Theme::select(&config, &themes);
+    let (locales, locale_errors) = Locales::load(&config);
+    for err in locale_errors {
+        warnings.push(format!("{err}\nlocale skipped"));
+    }
+    if locales.get(&config.display.language).is_none() {
+        warnings.push(format!(
+            "display.language: unknown language {:?}, using {}",
+            config.display.language,
+            config::locales::ENGLISH
+        ));
+    }
+    config.text = locales.text(&config.display.language);
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: config.window.title.clone(),
+            resolution: (config.window.width, config.window.height).into(),
+            mode: window_mode(config.display.window_mode),
+            resizable: true,
+            ..default()
+        }),
+        ..default()
+    }))
+    .insert_resource(ClearColor(theme.color(Role::Background)))
+    .insert_resource(ConfigResource(config))
+    .insert_resource(themes)
+    .insert_resource(locales)
+    .insert_resource(theme)
+    .insert_resource(mode)
+    .insert_resource(bindings)
+    .insert_resource(options)
+    .add_plugins(FrameTimeDiagnosticsPlugin::default())
+    .init_resource::<Shake>()
+    .init_resource::<CameraFocus>()
+    .init_resource::<GamepadPlayers>()
+    .add_message::<GameEvent>()
+    .insert_state(initial_state)
+    .add_sub_state::<PauseState>()
+    .add_systems(Startup, (setup_camera, setup_skin))
+    .add_systems(Update, load_skin)
+    .add_systems(PreUpdate, assign_gamepads)
+    .add_systems(Update, apply_theme.run_if(resource_changed::<Theme>))
+    .init_resource::<Focus>()
+    .init_resource::<KeyCapture>()
+    .add_systems(
+        PreUpdate,
+        navigate_buttons
+            .after(UiSystems::Focus)
+            .run_if(not_capturing),
+    )
+    .add_systems(Update, style_buttons.after(apply_theme))
+    .add_systems(
+        Update,
+        (sync_toggles, sync_sliders),
+    )
+    .add_actions::<MenuButtonAction>()
+    .add_actions::<SettingsButtonAction>()
+    .add_actions::<ControlsButtonAction>()
+    .add_actions::<GameOverAction>()
+    .add_actions::<PauseAction>()
+    .add_actions::<ModalButton>()
+    .add_message::<Confirmed>()
+    .add_systems(Update, (modal_input, exit_on_confirm))
+    .add_systems(OnEnter(AppState::Menu), setup_menu)
+    .add_systems(Update, menu_input.run_if(in_state(AppState::Menu)))
+    .add_systems(
+        OnExit(AppState::Menu),
+        (despawn_screen::<MenuRoot>, despawn_screen::<ModalRoot>),
+    )
+    .add_systems(OnEnter(AppState::Settings), setup_settings)
+    .add_systems(Update, settings_input.run_if(in_state(AppState::Settings)))
+    .add_systems(OnExit(AppState::Settings), despawn_screen::<SettingsRoot>)
+    .add_systems(OnEnter(AppState::Controls), setup_controls)
+    .add_systems(Update, controls_input.run_if(in_state(AppState::Controls)))
+    .add_systems(OnExit(AppState::Controls), despawn_screen::<ControlsRoot>)
+    .add_systems(
+        OnEnter(AppState::Playing),
+        (setup_hud, setup_minimap, setup_gameplay, resume_paused),
+    )
+    // The board stays up, dimmed, behind the game-over screen.
+    .add_systems(
+        OnExit(AppState::Playing),
+        (
+            (cleanup_gameplay, reset_camera).run_if(not(in_state(AppState::GameOver))),
+            cleanup_hud,
+            clear_effects,
+        ),
+    )
+    .add_systems(
+        OnEnter(AppState::GameOver),
+        (
+            setup_game_over,
+            delete_save.run_if(not(resource_exists::<ReplayPlayback>)),
+        ),
+    )
+    .add_systems(
+        Update,
+        game_over_input
+            .run_if(in_state(AppState::GameOver))
+            .run_if(no_modal),
+    )
+    .add_systems(
+        OnExit(AppState::GameOver),
+        (
+            despawn_screen::<GameOverRoot>,
+            despawn_screen::<ModalRoot>,
+            cleanup_gameplay,
+            reset_camera,
+        ),
+    )
+    .add_systems(OnEnter(PauseState::Paused), setup_pause)
+    .add_systems(
+        OnExit(PauseState::Paused),
+        (despawn_screen::<PauseRoot>, despawn_screen::<ModalRoot>),
+    )
+    .add_systems(
+        Update,
+        (
+            toggle_pause.run_if(no_modal).before(modal_input),
+            pause_input.run_if(in_state(PauseState::Paused)),
+            (pause_on_focus_lost, pause_on_gamepad_lost)
+                .run_if(in_state(PauseState::Running)),
         )
-        .add_systems(
-            Update,
-            update_fps_text.in_set(PlayingSet),
+            .run_if(in_state(AppState::Playing)),
+    )
+    .configure_sets(Update, PlayingSet.run_if(in_state(PauseState::Running)))
+    .configure_sets(
+        FixedUpdate,
+        PlayingSet.run_if(in_state(PauseState::Running)),
+    )
+    .add_systems(Update, (handle_input, restart_game).in_set(PlayingSet))
+    .add_systems(
+        FixedUpdate,
+        advance_game.run_if(not_rewinding).in_set(PlayingSet),
+    )
+    .add_systems(
+        Update,
+        (
+            rewind_game,
+            sync_board,
+            glide_snake,
+            spawn_effects,
+            play_death,
+            shatter_snake,
         )
-        .run();
+            .chain()
+            .after(handle_input)
+            .after(restart_game)
+            .in_set(PlayingSet),
+    )
+    .add_systems(
+        Update,
+        (update_particles, update_popups, shake_camera)
+            .after(spawn_effects)
+            .in_set(PlayingSet),
+    )
+    .add_systems(
+        Update,
+        (fit_board, follow_head)
+            .chain()
+            .after(sync_board)
+            .run_if(in_state(AppState::Playing).or(in_state(AppState::GameOver))),
+    )
+    .add_systems(
+        Update,
+        (update_fps_text, draw_minimap).run_if(in_state(AppState::Playing)),
+    )
+    .add_systems(
+        Update,
+        (autosave_on_focus_lost, autosave_on_close)
+            .run_if(in_state(AppState::Playing))
+            .run_if(not(resource_exists::<ReplayPlayback>)),
+    );
+    #[cfg(feature = "audio")]
+    app.add_plugins(crate::audio::SoundPlugin);
+    #[cfg(feature = "hot-reload")]
+    app.add_plugins(crate::hot_reload::HotReloadPlugin);
+    for warning in warnings {
+        warn!("{warning}");
+    }
+    app.run();
+    Ok(())
 }
 
 fn setup_camera(mut commands: Commands) {
@@ -0,0 +1,283 @@
+//! Sound, all of it generated when the game starts rather than loaded: a
+//! blip for turning, a rising chirp for eating and a falling one for dying,
+//! and a short tune that loops while the game runs. The tune plays one note
+//! per tick, so it speeds up and slows down with the game. Built only with
+//! the `audio` feature.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::audio::{AddAudioSource, Source, Volume};
+use beavy_config as config;
+
+use crate::gameplay::GameEvent;
+use crate::state::{AppState, ConfigResource, PauseState};
+
+pub(crate) struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Tone>()
+            .add_systems(Startup, setup_sounds)
+            .add_systems(
+                Update,
+                (play_sounds, follow_tempo)
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(
+                Update,
+                set_music_volume.run_if(resource_changed::<ConfigResource>),
+            )
+            .add_systems(OnEnter(PauseState::Paused), pause_music)
+            .add_systems(OnExit(PauseState::Paused), resume_music)
+            .add_systems(OnExit(AppState::Playing), stop_music);
+    }
+}
+
+/// Mono samples at [`config::audio::SAMPLE_RATE`], played as they are.
+#[derive(Asset, TypePath, Clone)]
+struct Tone(Arc<[f32]>);
+
+impl Decodable for Tone {
+    type DecoderItem = f32;
+    type Decoder = ToneDecoder;
+
+    fn decoder(&self) -> ToneDecoder {
+        ToneDecoder {
+            samples: self.0.clone(),
+            position: 0,
+        }
+    }
+}
+
+struct ToneDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for ToneDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for ToneDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        config::audio::SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / config::audio::SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+/// The sound effects, generated once at startup.
+#[derive(Resource)]
+struct Sounds {
+    turn: Handle<Tone>,
+    eat: Handle<Tone>,
+    death: Handle<Tone>,
+}
+
+/// The looping tune, made for notes `tick` seconds long.
+#[derive(Component)]
+struct Music {
+    tick: f32,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Wave {
+    Square,
+    Triangle,
+}
+
+impl Wave {
+    /// The wave's height a `phase` of the way through a cycle.
+    fn at(self, phase: f32) -> f32 {
+        let phase = phase.fract();
+        match self {
+            Wave::Square if phase < 0.5 => 1.0,
+            Wave::Square => -1.0,
+            Wave::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+        }
+    }
+}
+
+/// Loudness of the effects and of the music before the volume settings;
+/// square waves sound much louder than triangles at the same height.
+const EFFECT_LEVEL: f32 = 0.25;
+const MUSIC_LEVEL: f32 = 0.4;
+/// Seconds to fade in, so notes start without a click.
+const ATTACK: f32 = 0.005;
+/// The tune's lowest note, in hertz.
+const MUSIC_ROOT: f32 = 220.0;
+/// The tune, in semitones above [`MUSIC_ROOT`], one per tick.
+const MELODY: [i32; 16] = [0, 7, 12, 7, 3, 7, 10, 7, 0, 7, 12, 15, 14, 12, 10, 7];
+
+/// `seconds` of `wave` sliding from `from` to `to` hertz and fading out.
+fn synth(wave: Wave, from: f32, to: f32, seconds: f32, level: f32) -> Vec<f32> {
+    let rate = config::audio::SAMPLE_RATE as f32;
+    let count = (seconds * rate) as usize;
+    let mut phase = 0.0;
+    (0..count)
+        .map(|index| {
+            let done = index as f32 / count as f32;
+            phase += (from + (to - from) * done) / rate;
+            let envelope = (index as f32 / rate / ATTACK).min(1.0) * (1.0 - done);
+            wave.at(phase) * envelope * level
+        })
+        .collect()
+}
+
+/// One pass of [`MELODY`], each note `tick` seconds long.
+fn melody(tick: f32) -> Vec<f32> {
+    MELODY
+        .iter()
+        .flat_map(|&semitones| {
+            let pitch = MUSIC_ROOT * 2f32.powf(semitones as f32 / 12.0);
+            synth(Wave::Triangle, pitch, pitch, tick, MUSIC_LEVEL)
+        })
+        .collect()
+}
+
+fn tone(samples: Vec<f32>) -> Tone {
+    Tone(samples.into())
+}
+
+/// How loud to play a sound: the effects at the volume setting, the music
+/// quieter by its own setting, and nothing when muted.
+fn loudness(audio: &config::AudioConfig, music: bool) -> Volume {
+    let volume = match (audio.muted, music) {
+        (true, _) => 0.0,
+        (false, true) => audio.volume * audio.music_volume,
+        (false, false) => audio.volume,
+    };
+    Volume::Linear(volume)
+}
+
+fn setup_sounds(mut commands: Commands, mut tones: ResMut<Assets<Tone>>) {
+    let mut add =
+        |wave, from, to, seconds| tones.add(tone(synth(wave, from, to, seconds, EFFECT_LEVEL)));
+    commands.insert_resource(Sounds {
+        turn: add(Wave::Square, 660.0, 660.0, 0.04),
+        eat: add(Wave::Square, 520.0, 1040.0, 0.12),
+        death: add(Wave::Square, 440.0, 80.0, 0.6),
+    });
+}
+
+/// Plays the effect for each of this frame's game events.
+fn play_sounds(
+    mut commands: Commands,
+    mut events: MessageReader<GameEvent>,
+    config: Res<ConfigResource>,
+    sounds: Res<Sounds>,
+) {
+    let volume = loudness(&config.0.audio, false);
+    for event in events.read() {
+        let sound = match event {
+            GameEvent::Turned => &sounds.turn,
+            GameEvent::Ate { .. } => &sounds.eat,
+            GameEvent::Died => &sounds.death,
+        };
+        commands.spawn((
+            AudioPlayer(sound.clone()),
+            PlaybackSettings::DESPAWN.with_volume(volume),
+        ));
+    }
+}
+
+/// Starts the tune, and starts it again at the new tempo whenever the
+/// length of a tick changes.
+fn follow_tempo(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    fixed: Res<Time<Fixed>>,
+    mut tones: ResMut<Assets<Tone>>,
+    music: Query<(Entity, &Music)>,
+) {
+    let tick = fixed.timestep().as_secs_f32();
+    if music.iter().any(|(_, music)| music.tick == tick) {
+        return;
+    }
+    for (entity, _) in &music {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        AudioPlayer(tones.add(tone(melody(tick)))),
+        PlaybackSettings::LOOP.with_volume(loudness(&config.0.audio, true)),
+        Music { tick },
+    ));
+}
+
+fn set_music_volume(config: Res<ConfigResource>, mut sinks: Query<&mut AudioSink, With<Music>>) {
+    for mut sink in &mut sinks {
+        sink.set_volume(loudness(&config.0.audio, true));
+    }
+}
+
+fn pause_music(sinks: Query<&AudioSink, With<Music>>) {
+    for sink in &sinks {
+        sink.pause();
+    }
+}
+
+fn resume_music(sinks: Query<&AudioSink, With<Music>>) {
+    for sink in &sinks {
+        sink.play();
+    }
+}
+
+fn stop_music(mut commands: Commands, music: Query<Entity, With<Music>>) {
+    for entity in &music {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_last_as_long_as_asked_and_fade_out() {
+        let samples = synth(Wave::Square, 440.0, 440.0, 0.5, 1.0);
+        assert_eq!(samples.len(), config::audio::SAMPLE_RATE as usize / 2);
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+        assert!(samples[..100].iter().any(|sample| sample.abs() > 0.5));
+        assert!(samples.last().is_some_and(|sample| sample.abs() < 0.01));
+    }
+
+    #[test]
+    fn the_tune_plays_a_note_per_tick() {
+        let rate = config::audio::SAMPLE_RATE as f32;
+        assert_eq!(melody(0.1).len(), MELODY.len() * (0.1 * rate) as usize);
+        assert!(melody(0.2).len() > melody(0.1).len());
+    }
+
+    #[test]
+    fn muting_silences_everything() {
+        let mut audio = config::AudioConfig::default();
+        assert_eq!(
+            loudness(&audio, true),
+            Volume::Linear(audio.volume * audio.music_volume)
+        );
+        audio.muted = true;
+        assert_eq!(loudness(&audio, false), Volume::Linear(0.0));
+        assert_eq!(loudness(&audio, true), Volume::Linear(0.0));
+    }
+}
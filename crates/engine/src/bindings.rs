@@ -0,0 +1,298 @@
+use bevy::prelude::*;
+use beavy_config as config;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(crate) enum Action {
+    TurnUp,
+    TurnDown,
+    TurnLeft,
+    TurnRight,
+    Pause,
+    Restart,
+    Rewind,
+}
+
+impl Action {
+    pub(crate) const ALL: [Self; 7] = [
+        Self::TurnUp,
+        Self::TurnDown,
+        Self::TurnLeft,
+        Self::TurnRight,
+        Self::Pause,
+        Self::Restart,
+        Self::Rewind,
+    ];
+
+    pub(crate) fn label(self, text: &config::TextConfig) -> &str {
+        match self {
+            Self::TurnUp => &text.action_turn_up,
+            Self::TurnDown => &text.action_turn_down,
+            Self::TurnLeft => &text.action_turn_left,
+            Self::TurnRight => &text.action_turn_right,
+            Self::Pause => &text.action_pause,
+            Self::Restart => &text.action_restart,
+            Self::Rewind => &text.action_rewind,
+        }
+    }
+
+    fn keys(self, controls: &config::ControlsConfig) -> &[String] {
+        match self {
+            Self::TurnUp => &controls.turn_up,
+            Self::TurnDown => &controls.turn_down,
+            Self::TurnLeft => &controls.turn_left,
+            Self::TurnRight => &controls.turn_right,
+            Self::Pause => &controls.pause,
+            Self::Restart => &controls.restart,
+            Self::Rewind => &controls.rewind,
+        }
+    }
+
+    fn keys_mut(self, controls: &mut config::ControlsConfig) -> &mut Vec<String> {
+        match self {
+            Self::TurnUp => &mut controls.turn_up,
+            Self::TurnDown => &mut controls.turn_down,
+            Self::TurnLeft => &mut controls.turn_left,
+            Self::TurnRight => &mut controls.turn_right,
+            Self::Pause => &mut controls.pause,
+            Self::Restart => &mut controls.restart,
+            Self::Rewind => &mut controls.rewind,
+        }
+    }
+}
+
+/// Keys bound to each action, with `None` for an empty slot.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct KeyBindings {
+    slots: Vec<(Action, [Option<KeyCode>; config::controls::SLOTS])>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::from_config(&config::ControlsConfig::default()).0
+    }
+}
+
+impl KeyBindings {
+    /// Builds bindings from the config file, returning any key names that
+    /// are not recognised alongside them.
+    pub(crate) fn from_config(controls: &config::ControlsConfig) -> (Self, Vec<String>) {
+        let mut unknown = Vec::new();
+        let slots = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let mut keys = [None; config::controls::SLOTS];
+                for (slot, name) in keys.iter_mut().zip(action.keys(controls)) {
+                    *slot = key_from_name(name);
+                    if slot.is_none() {
+                        unknown.push(name.clone());
+                    }
+                }
+                (action, keys)
+            })
+            .collect();
+        (Self { slots }, unknown)
+    }
+
+    pub(crate) fn to_config(&self) -> config::ControlsConfig {
+        let mut controls = config::ControlsConfig::default();
+        for (action, keys) in &self.slots {
+            *action.keys_mut(&mut controls) = keys
+                .iter()
+                .flatten()
+                .filter_map(|key| key_name(*key))
+                .map(str::to_string)
+                .collect();
+        }
+        controls
+    }
+
+    pub(crate) fn keys(&self, action: Action) -> [Option<KeyCode>; config::controls::SLOTS] {
+        self.slots
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, keys)| *keys)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn just_pressed(&self, input: &ButtonInput<KeyCode>, action: Action) -> bool {
+        self.keys(action)
+            .into_iter()
+            .flatten()
+            .any(|key| input.just_pressed(key))
+    }
+
+    pub(crate) fn pressed(&self, input: &ButtonInput<KeyCode>, action: Action) -> bool {
+        self.keys(action)
+            .into_iter()
+            .flatten()
+            .any(|key| input.pressed(key))
+    }
+
+    /// The action and slot already using `key`, if any.
+    pub(crate) fn find(&self, key: KeyCode) -> Option<(Action, usize)> {
+        self.slots.iter().find_map(|(action, keys)| {
+            keys.iter()
+                .position(|bound| *bound == Some(key))
+                .map(|slot| (*action, slot))
+        })
+    }
+
+    /// Puts `key` in `action`'s `slot`. Fails with the conflicting binding if
+    /// another action or slot already uses the key.
+    pub(crate) fn bind(
+        &mut self,
+        action: Action,
+        slot: usize,
+        key: KeyCode,
+    ) -> Result<(), (Action, usize)> {
+        match self.find(key) {
+            Some(existing) if existing == (action, slot) => Ok(()),
+            Some(existing) => Err(existing),
+            None => {
+                if let Some((_, keys)) = self.slots.iter_mut().find(|(bound, _)| *bound == action) {
+                    keys[slot] = Some(key);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub(crate) fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, code)| *code == key)
+        .map(|(name, _)| *name)
+}
+
+pub(crate) fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, code)| *code)
+}
+
+/// Keys that can be bound, named after their `KeyCode` variants so the
+/// config file matches Bevy's documentation.
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("ArrowUp", KeyCode::ArrowUp),
+    ("ArrowDown", KeyCode::ArrowDown),
+    ("ArrowLeft", KeyCode::ArrowLeft),
+    ("ArrowRight", KeyCode::ArrowRight),
+    ("KeyA", KeyCode::KeyA),
+    ("KeyB", KeyCode::KeyB),
+    ("KeyC", KeyCode::KeyC),
+    ("KeyD", KeyCode::KeyD),
+    ("KeyE", KeyCode::KeyE),
+    ("KeyF", KeyCode::KeyF),
+    ("KeyG", KeyCode::KeyG),
+    ("KeyH", KeyCode::KeyH),
+    ("KeyI", KeyCode::KeyI),
+    ("KeyJ", KeyCode::KeyJ),
+    ("KeyK", KeyCode::KeyK),
+    ("KeyL", KeyCode::KeyL),
+    ("KeyM", KeyCode::KeyM),
+    ("KeyN", KeyCode::KeyN),
+    ("KeyO", KeyCode::KeyO),
+    ("KeyP", KeyCode::KeyP),
+    ("KeyQ", KeyCode::KeyQ),
+    ("KeyR", KeyCode::KeyR),
+    ("KeyS", KeyCode::KeyS),
+    ("KeyT", KeyCode::KeyT),
+    ("KeyU", KeyCode::KeyU),
+    ("KeyV", KeyCode::KeyV),
+    ("KeyW", KeyCode::KeyW),
+    ("KeyX", KeyCode::KeyX),
+    ("KeyY", KeyCode::KeyY),
+    ("KeyZ", KeyCode::KeyZ),
+    ("Digit0", KeyCode::Digit0),
+    ("Digit1", KeyCode::Digit1),
+    ("Digit2", KeyCode::Digit2),
+    ("Digit3", KeyCode::Digit3),
+    ("Digit4", KeyCode::Digit4),
+    ("Digit5", KeyCode::Digit5),
+    ("Digit6", KeyCode::Digit6),
+    ("Digit7", KeyCode::Digit7),
+    ("Digit8", KeyCode::Digit8),
+    ("Digit9", KeyCode::Digit9),
+    ("Numpad0", KeyCode::Numpad0),
+    ("Numpad1", KeyCode::Numpad1),
+    ("Numpad2", KeyCode::Numpad2),
+    ("Numpad3", KeyCode::Numpad3),
+    ("Numpad4", KeyCode::Numpad4),
+    ("Numpad5", KeyCode::Numpad5),
+    ("Numpad6", KeyCode::Numpad6),
+    ("Numpad7", KeyCode::Numpad7),
+    ("Numpad8", KeyCode::Numpad8),
+    ("Numpad9", KeyCode::Numpad9),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Escape", KeyCode::Escape),
+    ("Backspace", KeyCode::Backspace),
+    ("Tab", KeyCode::Tab),
+    ("ShiftLeft", KeyCode::ShiftLeft),
+    ("ShiftRight", KeyCode::ShiftRight),
+    ("ControlLeft", KeyCode::ControlLeft),
+    ("ControlRight", KeyCode::ControlRight),
+    ("AltLeft", KeyCode::AltLeft),
+    ("AltRight", KeyCode::AltRight),
+    ("Comma", KeyCode::Comma),
+    ("Period", KeyCode::Period),
+    ("Semicolon", KeyCode::Semicolon),
+    ("Quote", KeyCode::Quote),
+    ("Slash", KeyCode::Slash),
+    ("Backslash", KeyCode::Backslash),
+    ("BracketLeft", KeyCode::BracketLeft),
+    ("BracketRight", KeyCode::BracketRight),
+    ("Minus", KeyCode::Minus),
+    ("Equal", KeyCode::Equal),
+    ("Backquote", KeyCode::Backquote),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Insert", KeyCode::Insert),
+    ("Delete", KeyCode::Delete),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_round_trip_through_config() {
+        let (bindings, unknown) = KeyBindings::from_config(&config::ControlsConfig::default());
+
+        assert!(unknown.is_empty());
+        assert_eq!(bindings.to_config(), config::ControlsConfig::default());
+        assert_eq!(
+            bindings.keys(Action::TurnUp),
+            [Some(KeyCode::ArrowUp), Some(KeyCode::KeyW)]
+        );
+    }
+
+    #[test]
+    fn bind_rejects_keys_used_elsewhere() {
+        let mut bindings = KeyBindings::default();
+
+        assert_eq!(
+            bindings.bind(Action::Pause, 0, KeyCode::KeyW),
+            Err((Action::TurnUp, 1))
+        );
+        assert_eq!(bindings.bind(Action::TurnUp, 1, KeyCode::KeyZ), Ok(()));
+        assert_eq!(bindings.find(KeyCode::KeyZ), Some((Action::TurnUp, 1)));
+        assert_eq!(bindings.find(KeyCode::KeyW), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Where the camera looks. The board is laid out in world units of
+//! `grid.cell_size` per cell, and by default the camera is zoomed so the
+//! whole of it shows with [`config::grid::BOARD_MARGIN`] clear around it for
+//! the HUD. Whatever the window has to spare in the other direction is left
+//! as background on either side.
+//!
+//! With `display.follow_camera` set, a board too large to show whole at a
+//! readable size is instead shown a part at a time: the camera goes after
+//! the snake's head once it leaves a dead zone in the middle of the view,
+//! easing towards it and never showing past the board's edges.
+//!
+//! Either way the borders are laid out again as the zoom changes, so they
+//! stay `grid.border_thickness` pixels thick on screen.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use beavy_config as config;
+
+use crate::gameplay::{board_size, border_layout, grid_to_world, BorderSegment};
+use crate::state::{ConfigResource, GameResource};
+
+/// Where the camera has been moved to, apart from any shake. `None` until
+/// it first looks at a board, so it starts there rather than easing over.
+#[derive(Resource, Default)]
+pub(crate) struct CameraFocus(Option<Vec2>);
+
+/// World units per pixel that fit a board of `board` world units, with its
+/// borders `border` pixels thick and the margin clear around them, into a
+/// window of `window` pixels.
+pub(crate) fn board_scale(board: Vec2, border: f32, window: Vec2) -> f32 {
+    let room = window - 2.0 * (config::grid::BOARD_MARGIN + border);
+    (board / room.max(Vec2::ONE)).max_element()
+}
+
+/// Where a camera looking at `focus` should look so that `head` is inside
+/// its dead zone, without showing past the edges of a `board` centred on
+/// the origin. `view` is how much of the world the camera shows.
+fn follow_target(focus: Vec2, head: Vec2, view: Vec2, board: Vec2) -> Vec2 {
+    let zone = view * config::camera::DEAD_ZONE;
+    let offset = head - focus;
+    let target = focus + offset - offset.clamp(-zone, zone);
+    let limit = ((board - view) / 2.0).max(Vec2::ZERO);
+    target.clamp(-limit, limit)
+}
+
+/// Zooms the camera to fit the board to the window, or while following to
+/// the size cells are shown at, following resizes and changes to the
+/// board's size.
+pub(crate) fn fit_board(
+    config: Res<ConfigResource>,
+    state: Res<GameResource>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Projection, With<Camera2d>>,
+    mut borders: Query<(&BorderSegment, &mut Sprite, &mut Transform)>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let grid = &config.0.grid;
+    let board = board_size(grid.cell_size, &state.0);
+    let mut scale = board_scale(board, grid.border_thickness, window.size());
+    if config.0.display.follow_camera {
+        scale = scale.min(grid.cell_size / config::camera::FOLLOW_CELL_PIXELS);
+    }
+    for mut projection in &mut cameras {
+        let Projection::Orthographic(orthographic) = projection.bypass_change_detection() else {
+            continue;
+        };
+        if orthographic.scale != scale {
+            orthographic.scale = scale;
+            projection.set_changed();
+        }
+    }
+
+    let layout = border_layout(board, grid.border_thickness * scale);
+    for (BorderSegment(side), mut sprite, mut transform) in &mut borders {
+        let (size, translation) = layout[*side];
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
+
+/// Moves the camera after the snake's head while following, or back to the
+/// middle of the board. It is moved by the change in focus rather than put
+/// in place, so the screen shake still adds to it.
+pub(crate) fn follow_head(
+    time: Res<Time>,
+    config: Res<ConfigResource>,
+    state: Res<GameResource>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut focus: ResMut<CameraFocus>,
+    mut cameras: Query<(&Projection, &mut Transform), With<Camera2d>>,
+) {
+    let (Ok(window), Ok((Projection::Orthographic(orthographic), mut transform))) =
+        (windows.single(), cameras.single_mut())
+    else {
+        return;
+    };
+    let grid = &config.0.grid;
+    let state = &state.0;
+    let current = focus.0.unwrap_or(Vec2::ZERO);
+    let target = match state.snake.first() {
+        Some(head) if config.0.display.follow_camera => {
+            let head = grid_to_world(grid.cell_size, state.grid_width, state.grid_height, *head);
+            let head = head.truncate();
+            let view = window.size() * orthographic.scale;
+            let board = board_size(grid.cell_size, state);
+            follow_target(focus.0.unwrap_or(head), head, view, board)
+        }
+        _ => Vec2::ZERO,
+    };
+    let next = match focus.0 {
+        Some(focus) => {
+            let ease = 1.0 - (-config::camera::SMOOTHING * time.delta_secs()).exp();
+            let eased = focus.lerp(target, ease);
+            if eased.distance(target) < 0.01 {
+                target
+            } else {
+                eased
+            }
+        }
+        None => target,
+    };
+    if focus.0 != Some(next) {
+        transform.translation += (next - current).extend(0.0);
+        focus.0 = Some(next);
+    }
+}
+
+/// Puts the camera back in the middle for the next board.
+pub(crate) fn reset_camera(
+    mut focus: ResMut<CameraFocus>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    if let Some(position) = focus.0.take() {
+        for mut transform in &mut cameras {
+            transform.translation -= position.extend(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_boards_are_enlarged_and_large_ones_shrunk() {
+        let window = Vec2::new(960.0, 540.0);
+        let margin = 2.0 * config::grid::BOARD_MARGIN;
+
+        let small = board_scale(Vec2::new(480.0, 360.0), 0.0, window);
+        assert!((small - 360.0 / (540.0 - margin)).abs() < 1e-6);
+        let large = board_scale(Vec2::new(4800.0, 1200.0), 0.0, window);
+        assert!((large - 4800.0 / (960.0 - margin)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn following_waits_for_the_head_to_leave_the_dead_zone_and_stops_at_the_edges() {
+        let view = Vec2::new(400.0, 200.0);
+        let board = Vec2::new(2000.0, 2000.0);
+
+        let inside = Vec2::new(60.0, -30.0);
+        assert_eq!(follow_target(Vec2::ZERO, inside, view, board), Vec2::ZERO);
+        let beyond = Vec2::new(200.0, 0.0);
+        assert_eq!(
+            follow_target(Vec2::ZERO, beyond, view, board),
+            Vec2::new(120.0, 0.0)
+        );
+        let corner = Vec2::new(990.0, -990.0);
+        assert_eq!(
+            follow_target(corner, corner, view, board),
+            Vec2::new(800.0, -900.0)
+        );
+        // A board smaller than the view stays in the middle.
+        let small = Vec2::new(300.0, 100.0);
+        assert_eq!(follow_target(Vec2::ZERO, beyond, view, small), Vec2::ZERO);
+    }
+
+    #[test]
+    fn the_camera_follows_the_window_and_borders_keep_their_thickness() {
+        let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.insert_resource(GameResource(game::new_game(20, 15)));
+        app.add_systems(Update, fit_board);
+        let window = app
+            .world_mut()
+            .spawn((
+                Window {
+                    resolution: (960, 540).into(),
+                    ..default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+        app.world_mut().spawn(Camera2d);
+        let border = app
+            .world_mut()
+            .spawn((BorderSegment(0), Sprite::default(), Transform::default()))
+            .id();
+
+        let scale = |app: &mut App| {
+            let world = app.world_mut();
+            match world.query::<&Projection>().single(world) {
+                Ok(Projection::Orthographic(orthographic)) => orthographic.scale,
+                _ => panic!("one orthographic camera"),
+            }
+        };
+        app.update();
+        let before = scale(&mut app);
+        app.world_mut()
+            .get_mut::<Window>(window)
+            .expect("window")
+            .resolution
+            .set(1920.0, 1080.0);
+        app.update();
+        let after = scale(&mut app);
+        assert!(after < before);
+
+        let thickness = config::grid::BORDER_THICKNESS;
+        let sprite = app.world().get::<Sprite>(border).expect("border sprite");
+        let size = sprite.custom_size.expect("border size");
+        assert!((size.y - thickness * after).abs() < 1e-4);
+    }
+}
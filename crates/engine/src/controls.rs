@@ -0,0 +1,264 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use beavy_config as config;
+
+use crate::bindings::{key_name, Action, KeyBindings};
+use crate::focus::BackButton;
+use crate::state::{AppState, ConfigResource};
+use crate::theme::{Role, Theme};
+use crate::widgets::{spawn_screen, Pressed};
+
+#[derive(Component)]
+pub(crate) struct ControlsRoot;
+
+#[derive(Component, Copy, Clone)]
+pub(crate) enum ControlsButtonAction {
+    Slot(Action, usize),
+    Reset,
+    Back,
+}
+
+/// A button showing the key bound to one slot of an action.
+#[derive(Component)]
+pub(crate) struct KeySlot(Action, usize);
+
+#[derive(Component)]
+pub(crate) struct ControlsMessage;
+
+/// The slot waiting for a key, if any. Menu navigation is off meanwhile so
+/// that arrows and Enter can be bound too; Escape gives up on the slot.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct KeyCapture(Option<(Action, usize)>);
+
+pub(crate) fn not_capturing(capture: Option<Res<KeyCapture>>) -> bool {
+    capture.is_none_or(|capture| capture.0.is_none())
+}
+
+pub(crate) fn setup_controls(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    bindings: Res<KeyBindings>,
+) {
+    let config = &config.0;
+    spawn_screen(
+        &mut commands,
+        config,
+        &theme,
+        Role::MenuBg,
+        ControlsRoot,
+        |ui| {
+            ui.title(&config.text.controls_title);
+            let size = (
+                px(config::ui::KEY_SLOT_WIDTH),
+                px(config::ui::KEY_SLOT_HEIGHT),
+            );
+            for action in Action::ALL {
+                ui.field(action.label(&config.text), |ui| {
+                    for slot in 0..config::controls::SLOTS {
+                        let label = slot_label(&config.text, &bindings, None, action, slot);
+                        ui.sized_button(&label, size, ControlsButtonAction::Slot(action, slot))
+                            .insert(KeySlot(action, slot));
+                    }
+                });
+            }
+            ui.text("", config.ui.hud_font_size, Role::Text)
+                .insert(ControlsMessage);
+            ui.row(|ui| {
+                ui.button(&config.text.controls_reset, ControlsButtonAction::Reset);
+                ui.button(&config.text.settings_back, ControlsButtonAction::Back)
+                    .insert(BackButton);
+            });
+        },
+    );
+}
+
+/// Handles the screen's buttons, and while a slot is waiting for a key,
+/// binds the next key pressed or reports which action already uses it.
+/// Escape leaves the slot as it was.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn controls_input(
+    mut pressed: MessageReader<Pressed<ControlsButtonAction>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut config: ResMut<ConfigResource>,
+    mut bindings: ResMut<KeyBindings>,
+    mut capture: ResMut<KeyCapture>,
+    slots: Query<(&KeySlot, &Children)>,
+    mut labels: Query<&mut Text, Without<ControlsMessage>>,
+    mut message: Query<&mut Text, With<ControlsMessage>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let mut note = None;
+
+    for Pressed(action) in pressed.read() {
+        match *action {
+            ControlsButtonAction::Slot(action, slot) => {
+                capture.0 = Some((action, slot));
+                note = Some(String::new());
+            }
+            ControlsButtonAction::Reset => {
+                *bindings = KeyBindings::default();
+                capture.0 = None;
+                note = Some(String::new());
+            }
+            ControlsButtonAction::Back => {
+                let controls = bindings.to_config();
+                if config.0.controls != controls {
+                    config.0.controls = controls;
+                }
+                match save_controls(&config.0.controls) {
+                    Ok(path) => info!("controls saved to {}", path.display()),
+                    Err(err) => warn!("controls could not be saved: {err}"),
+                }
+                capture.0 = None;
+                next_state.set(AppState::Settings);
+                return;
+            }
+        }
+    }
+
+    // Skipped on the frame a slot is chosen, so the Enter that chose it from
+    // the keyboard is not bound straight away.
+    let text = &config.0.text;
+    if let Some((action, slot)) = capture.0
+        && note.is_none()
+    {
+        let pressed = keys
+            .get_just_pressed()
+            .find(|key| key_name(**key).is_some());
+        if keys.just_pressed(KeyCode::Escape) {
+            capture.0 = None;
+            note = Some(String::new());
+        } else if let Some(key) = pressed {
+            match bindings.bind(action, slot, *key) {
+                Ok(()) => {
+                    capture.0 = None;
+                    note = Some(String::new());
+                }
+                Err((other, _)) => {
+                    note = Some(
+                        text.controls_conflict
+                            .replace("{key}", &display_name(*key))
+                            .replace("{action}", other.label(text)),
+                    );
+                }
+            }
+        }
+    }
+
+    if bindings.is_changed() || note.is_some() {
+        for (slot, children) in &slots {
+            let mut texts = labels.iter_many_mut(children);
+            while let Some(mut label) = texts.fetch_next() {
+                **label = slot_label(text, &bindings, capture.0, slot.0, slot.1);
+            }
+        }
+    }
+    if let (Some(note), Ok(mut message)) = (note, message.single_mut()) {
+        **message = note;
+    }
+}
+
+/// Writes the bindings into the config file, leaving its other values alone.
+fn save_controls(controls: &config::ControlsConfig) -> Result<PathBuf, config::ConfigError> {
+    let mut saved = config::load()?;
+    saved.controls = controls.clone();
+    config::save(&saved)
+}
+
+fn slot_label(
+    text: &config::TextConfig,
+    bindings: &KeyBindings,
+    capture: Option<(Action, usize)>,
+    action: Action,
+    slot: usize,
+) -> String {
+    if capture == Some((action, slot)) {
+        return text.controls_prompt.clone();
+    }
+    match bindings.keys(action)[slot] {
+        Some(key) => display_name(key),
+        None => text.controls_unbound.clone(),
+    }
+}
+
+/// `KeyW` and `Digit1` read better as `W` and `1`.
+fn display_name(key: KeyCode) -> String {
+    let name = key_name(key).unwrap_or_default();
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    use crate::widgets::WidgetApp;
+
+    #[test]
+    fn setup_controls_spawns_a_slot_per_binding() {
+        let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
+        app.init_resource::<KeyBindings>();
+        app.add_systems(Startup, setup_controls);
+        app.update();
+
+        let world = app.world_mut();
+        let mut query = world.query::<&KeySlot>();
+        assert_eq!(
+            query.iter(world).count(),
+            Action::ALL.len() * config::controls::SLOTS
+        );
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.clear();
+        keys.press(key);
+        app.update();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(key);
+    }
+
+    #[test]
+    fn escape_gives_up_on_a_slot_after_a_conflict() {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin);
+        app.insert_resource(ButtonInput::<KeyCode>::default());
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
+        app.init_resource::<KeyBindings>();
+        app.insert_resource(KeyCapture(Some((Action::TurnUp, 0))));
+        app.insert_state(AppState::Controls);
+        app.add_actions::<ControlsButtonAction>();
+        app.add_systems(Startup, setup_controls);
+        app.add_systems(Update, controls_input);
+        app.update();
+
+        let before = app.world().resource::<KeyBindings>().keys(Action::TurnUp)[0];
+        let taken = app.world().resource::<KeyBindings>().keys(Action::TurnDown)[0].unwrap();
+        press(&mut app, taken);
+        assert!(app.world().resource::<KeyCapture>().0.is_some());
+
+        press(&mut app, KeyCode::Escape);
+        assert!(app.world().resource::<KeyCapture>().0.is_none());
+        assert_eq!(
+            app.world().resource::<KeyBindings>().keys(Action::TurnUp)[0],
+            before
+        );
+    }
+
+    #[test]
+    fn key_names_drop_their_prefix() {
+        assert_eq!(display_name(KeyCode::KeyW), "W");
+        assert_eq!(display_name(KeyCode::Digit1), "1");
+        assert_eq!(display_name(KeyCode::ArrowUp), "ArrowUp");
+    }
+}
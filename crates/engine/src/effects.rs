@@ -0,0 +1,353 @@
+//! Feedback for what happens on the board: a burst of particles and a
+//! floating score where food is eaten, and a shake of the screen when the
+//! snake dies, which breaks apart once it has flashed for a while. Nothing
+//! here affects the game, and all of it is left out with
+//! `display.reduced_motion` set.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use beavy_config as config;
+
+use crate::gameplay::{grid_to_world, Dying, GameEvent, SnakeSegment};
+use crate::state::{ConfigResource, GameResource};
+use crate::theme::{Role, Theme};
+
+/// A sprite flying off and fading out, spinning `spin` radians a second.
+#[derive(Component)]
+pub(crate) struct Particle {
+    velocity: Vec2,
+    spin: f32,
+    life: Timer,
+}
+
+impl Particle {
+    fn new(velocity: Vec2, spin: f32, seconds: f32) -> Self {
+        Self {
+            velocity,
+            spin,
+            life: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
+/// Points scored, rising from where they were won and fading out.
+#[derive(Component)]
+pub(crate) struct Popup {
+    origin: Vec3,
+    life: Timer,
+}
+
+/// Shakes the camera until `timer` runs out. `offset` is how far it has
+/// been moved, so other systems placing the camera are not disturbed.
+#[derive(Resource)]
+pub(crate) struct Shake {
+    timer: Timer,
+    offset: Vec3,
+}
+
+impl Default for Shake {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(config::effects::SHAKE_SECONDS, TimerMode::Once);
+        timer.finish();
+        Self {
+            timer,
+            offset: Vec3::ZERO,
+        }
+    }
+}
+
+/// Starts the effects for this frame's game events.
+pub(crate) fn spawn_effects(
+    mut commands: Commands,
+    mut events: MessageReader<GameEvent>,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    state: Res<GameResource>,
+    mut shake: ResMut<Shake>,
+) {
+    let config = &config.0;
+    if config.display.reduced_motion {
+        events.clear();
+        return;
+    }
+    for event in events.read() {
+        match *event {
+            GameEvent::Ate { cell, points } => {
+                let position = grid_to_world(
+                    config.grid.cell_size,
+                    state.0.grid_width,
+                    state.0.grid_height,
+                    cell,
+                );
+                burst(&mut commands, &theme, position);
+                popup(&mut commands, config, &theme, position, points);
+            }
+            GameEvent::Turned => {}
+            GameEvent::Died => shake.timer.reset(),
+        }
+    }
+}
+
+/// Breaks the dying snake apart once it has flashed for its share of the
+/// time before the game-over screen.
+#[allow(clippy::type_complexity)]
+pub(crate) fn shatter_snake(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    dying: Option<Res<Dying>>,
+    mut segments: Query<(&Sprite, &Transform, &mut Visibility), With<SnakeSegment>>,
+) {
+    let Some(dying) = dying else {
+        return;
+    };
+    if config.0.display.reduced_motion || dying.progress() < config::effects::SHATTER_AT {
+        return;
+    }
+    for (sprite, transform, mut visibility) in &mut segments {
+        if *visibility != Visibility::Hidden {
+            break_off(&mut commands, sprite, transform, dying.remaining_secs());
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// A random unit vector.
+fn random_direction() -> Vec2 {
+    Vec2::from_angle(rand::random::<f32>() * TAU)
+}
+
+fn burst(commands: &mut Commands, theme: &Theme, position: Vec3) {
+    let size = Vec2::splat(config::effects::PARTICLE_SIZE);
+    for _ in 0..config::effects::EAT_PARTICLES {
+        let speed = config::effects::PARTICLE_SPEED * (0.5 + rand::random::<f32>() / 2.0);
+        commands.spawn((
+            Sprite::from_color(theme.color(Role::Food), size),
+            Transform::from_translation(position.with_z(config::effects::PARTICLE_Z)),
+            Particle::new(
+                random_direction() * speed,
+                0.0,
+                config::effects::PARTICLE_SECONDS,
+            ),
+        ));
+    }
+}
+
+fn popup(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    position: Vec3,
+    points: u32,
+) {
+    let origin = position.with_z(config::effects::POPUP_Z);
+    commands.spawn((
+        Text2d::new(
+            config
+                .text
+                .score_popup
+                .replace("{points}", &config.text.format_number(points.into())),
+        ),
+        TextFont {
+            font_size: config::effects::POPUP_FONT_SIZE,
+            ..default()
+        },
+        TextColor(theme.color(Role::Text)),
+        Transform::from_translation(origin),
+        Popup {
+            origin,
+            life: Timer::from_seconds(config::effects::POPUP_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+/// Replaces a segment of the snake with a copy that flies off on its own
+/// for `seconds`.
+fn break_off(commands: &mut Commands, sprite: &Sprite, transform: &Transform, seconds: f32) {
+    let speed = config::effects::DEATH_PIECE_SPEED * (0.5 + rand::random::<f32>() / 2.0);
+    let spin = config::effects::DEATH_PIECE_SPIN * (rand::random::<f32>() * 2.0 - 1.0);
+    commands.spawn((
+        sprite.clone(),
+        transform.with_translation(transform.translation.with_z(config::effects::PARTICLE_Z)),
+        Particle::new(random_direction() * speed, spin, seconds),
+    ));
+}
+
+pub(crate) fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in &mut particles {
+        if particle.life.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        transform.rotate_z(particle.spin * delta);
+        particle.velocity *= (1.0 - config::effects::PARTICLE_DRAG * delta).max(0.0);
+        sprite.color.set_alpha(particle.life.fraction_remaining());
+    }
+}
+
+pub(crate) fn update_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popups: Query<(Entity, &mut Popup, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut transform, mut color) in &mut popups {
+        if popup.life.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let rise = config::effects::POPUP_RISE * popup.life.fraction();
+        transform.translation = popup.origin + Vec3::Y * rise;
+        color.0.set_alpha(popup.life.fraction_remaining());
+    }
+}
+
+/// Moves the camera by a random amount that dies down as the shake ends.
+pub(crate) fn shake_camera(
+    time: Res<Time>,
+    mut shake: ResMut<Shake>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    let offset = if shake.timer.tick(time.delta()).is_finished() {
+        Vec3::ZERO
+    } else {
+        let strength = config::effects::SHAKE_STRENGTH * shake.timer.fraction_remaining();
+        (random_direction() * strength).extend(0.0)
+    };
+    if offset == shake.offset {
+        return;
+    }
+    for mut transform in &mut cameras {
+        transform.translation += offset - shake.offset;
+    }
+    shake.offset = offset;
+}
+
+/// Removes whatever effects are still playing when the game is left.
+#[allow(clippy::type_complexity)]
+pub(crate) fn clear_effects(
+    mut commands: Commands,
+    effects: Query<Entity, Or<(With<Particle>, With<Popup>)>>,
+    mut shake: ResMut<Shake>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    for entity in &effects {
+        commands.entity(entity).despawn();
+    }
+    for mut transform in &mut cameras {
+        transform.translation -= shake.offset;
+    }
+    *shake = Shake::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::{TimePlugin, TimeUpdateStrategy};
+
+    use super::*;
+
+    fn effects_app(reduced_motion: bool) -> App {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        let mut config = ConfigResource::default();
+        config.0.display.reduced_motion = reduced_motion;
+        app.insert_resource(config);
+        app.init_resource::<Theme>();
+        app.init_resource::<Shake>();
+        app.insert_resource(GameResource(game::new_game(20, 20)));
+        app.add_message::<GameEvent>();
+        app.add_systems(
+            Update,
+            (
+                spawn_effects,
+                shatter_snake,
+                update_particles,
+                update_popups,
+                shake_camera,
+            )
+                .chain(),
+        );
+        app.world_mut().spawn((Camera2d, Transform::default()));
+        app
+    }
+
+    fn count<C: Component>(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world.query::<&C>().iter(world).count()
+    }
+
+    fn eat(app: &mut App) {
+        app.world_mut().write_message(GameEvent::Ate {
+            cell: game::GridPos { x: 3, y: 4 },
+            points: 1,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn eating_bursts_and_shows_the_points_until_they_fade() {
+        let mut app = effects_app(false);
+        eat(&mut app);
+        assert_eq!(count::<Particle>(&mut app), config::effects::EAT_PARTICLES);
+        assert_eq!(count::<Popup>(&mut app), 1);
+
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(count::<Particle>(&mut app), 0);
+        assert_eq!(count::<Popup>(&mut app), 0);
+    }
+
+    #[test]
+    fn the_dying_snake_breaks_apart_after_flashing_and_the_shake_settles() {
+        let mut app = effects_app(false);
+        let segment = app
+            .world_mut()
+            .spawn((Sprite::default(), Transform::default(), Visibility::Visible))
+            .id();
+        app.world_mut()
+            .entity_mut(segment)
+            .insert(SnakeSegment::at(0, Vec3::ZERO));
+        app.update();
+        app.world_mut().write_message(GameEvent::Died);
+        app.insert_resource(Dying::new(1.0));
+        app.update();
+        assert_eq!(count::<Particle>(&mut app), 0);
+
+        // Past the share of the time spent flashing; with no time left the
+        // pieces are gone again at once.
+        app.insert_resource(Dying::new(0.0));
+        app.update();
+        assert_eq!(
+            app.world().get::<Visibility>(segment),
+            Some(&Visibility::Hidden)
+        );
+        for _ in 0..10 {
+            app.update();
+        }
+        let world = app.world_mut();
+        let camera = world
+            .query_filtered::<&Transform, With<Camera2d>>()
+            .single(world)
+            .expect("one camera");
+        assert!(camera.translation.length() < 1e-4);
+    }
+
+    #[test]
+    fn reduced_motion_leaves_the_effects_out() {
+        let mut app = effects_app(true);
+        eat(&mut app);
+        assert_eq!(count::<Particle>(&mut app), 0);
+        assert_eq!(count::<Popup>(&mut app), 0);
+    }
+}
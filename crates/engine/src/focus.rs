@@ -0,0 +1,291 @@
+//! Keyboard and gamepad control of the menus.
+//!
+//! One button at a time holds the focus. Arrow keys and the D-pad move it to
+//! the nearest button in that direction, Tab walks through the buttons in
+//! reading order, and Enter, Space or the gamepad's south button activate it
+//! by pressing it the same way a click would, so each screen keeps handling
+//! `Interaction::Pressed` only. Escape or the east button activate the
+//! screen's [`BackButton`]. While a dialog is open only its buttons can be
+//! reached.
+
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::modal::ModalButton;
+use crate::theme::{Role, Theme, ThemeBorder, ThemeColor};
+
+/// The button Escape or the gamepad's east button activates.
+#[derive(Component)]
+pub(crate) struct BackButton;
+
+/// The button keyboard and gamepad input acts on, if any.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct Focus(pub(crate) Option<Entity>);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Nav {
+    Move(Vec2),
+    Next,
+    Previous,
+    Activate,
+    Back,
+}
+
+fn read_nav(keys: &ButtonInput<KeyCode>, gamepads: &Query<&Gamepad>) -> Option<Nav> {
+    let key = |code| keys.just_pressed(code);
+    let pad = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    if key(KeyCode::ArrowUp) || pad(GamepadButton::DPadUp) {
+        Some(Nav::Move(Vec2::NEG_Y))
+    } else if key(KeyCode::ArrowDown) || pad(GamepadButton::DPadDown) {
+        Some(Nav::Move(Vec2::Y))
+    } else if key(KeyCode::ArrowLeft) || pad(GamepadButton::DPadLeft) {
+        Some(Nav::Move(Vec2::NEG_X))
+    } else if key(KeyCode::ArrowRight) || pad(GamepadButton::DPadRight) {
+        Some(Nav::Move(Vec2::X))
+    } else if key(KeyCode::Tab) {
+        if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            Some(Nav::Previous)
+        } else {
+            Some(Nav::Next)
+        }
+    } else if key(KeyCode::Enter)
+        || key(KeyCode::NumpadEnter)
+        || key(KeyCode::Space)
+        || pad(GamepadButton::South)
+    {
+        Some(Nav::Activate)
+    } else if key(KeyCode::Escape) || pad(GamepadButton::East) {
+        Some(Nav::Back)
+    } else {
+        None
+    }
+}
+
+/// Moves the focus and activates buttons. Runs after the UI's own pointer
+/// handling so a press made here is what the screens see this frame; it is
+/// released again on the next one.
+#[allow(clippy::type_complexity)]
+pub(crate) fn navigate_buttons(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut focus: ResMut<Focus>,
+    mut buttons: Query<
+        (
+            Entity,
+            &UiGlobalTransform,
+            &mut Interaction,
+            Has<BackButton>,
+            Has<ModalButton>,
+        ),
+        With<Button>,
+    >,
+    mut pressed: Local<Option<Entity>>,
+) {
+    if let Some(entity) = pressed.take()
+        && let Ok((_, _, mut interaction, _, _)) = buttons.get_mut(entity)
+        && *interaction == Interaction::Pressed
+    {
+        *interaction = Interaction::None;
+    }
+    // A click moves the focus as well, so the keys carry on from there.
+    for (entity, _, interaction, ..) in &mut buttons {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            focus.0 = Some(entity);
+        }
+    }
+    let Some(nav) = read_nav(&keys, &gamepads) else {
+        return;
+    };
+
+    let in_modal = buttons.iter().any(|(.., modal)| modal);
+    let mut targets: Vec<(Entity, Vec2, bool)> = buttons
+        .iter()
+        .filter(|(.., modal)| *modal == in_modal)
+        .map(|(entity, transform, _, back, _)| (entity, transform.translation, back))
+        .collect();
+    targets.sort_by(|a, b| reading_order(a.1, b.1));
+    let current = focus
+        .0
+        .and_then(|entity| targets.iter().position(|target| target.0 == entity));
+
+    let target = match (nav, current) {
+        (Nav::Back, _) => targets
+            .iter()
+            .find(|target| target.2)
+            .map(|target| target.0),
+        (_, None) => targets.first().map(|target| target.0),
+        (Nav::Activate, Some(index)) => Some(targets[index].0),
+        (Nav::Next, Some(index)) => Some(targets[(index + 1) % targets.len()].0),
+        (Nav::Previous, Some(index)) => {
+            Some(targets[(index + targets.len() - 1) % targets.len()].0)
+        }
+        (Nav::Move(direction), Some(index)) => {
+            let from = targets[index].1;
+            nearest(from, direction, &targets).or(Some(targets[index].0))
+        }
+    };
+    let Some(target) = target else {
+        return;
+    };
+    // The first key press on a screen only shows where the focus is.
+    let activate = match nav {
+        Nav::Back => true,
+        Nav::Activate => current.is_some(),
+        _ => false,
+    };
+    focus.0 = Some(target);
+    if activate && let Ok((_, _, mut interaction, _, _)) = buttons.get_mut(target) {
+        *interaction = Interaction::Pressed;
+        *pressed = Some(target);
+    }
+}
+
+/// Top to bottom, then left to right; UI coordinates grow downwards.
+fn reading_order(a: Vec2, b: Vec2) -> Ordering {
+    a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+}
+
+/// The closest button ahead of `from` in `direction`, preferring ones that
+/// are roughly in line with it.
+fn nearest(from: Vec2, direction: Vec2, targets: &[(Entity, Vec2, bool)]) -> Option<Entity> {
+    targets
+        .iter()
+        .filter_map(|(entity, position, _)| {
+            let offset = *position - from;
+            let ahead = offset.dot(direction);
+            (ahead > 0.5).then(|| (ahead + 2.0 * offset.perp_dot(direction).abs(), *entity))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity)| entity)
+}
+
+/// Shows hover, focus and press on every button in the theme's colours.
+#[allow(clippy::type_complexity)]
+pub(crate) fn style_buttons(
+    theme: Res<Theme>,
+    focus: Res<Focus>,
+    mut buttons: Query<
+        (
+            Entity,
+            &Interaction,
+            &ThemeColor,
+            &ThemeBorder,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        With<Button>,
+    >,
+) {
+    for (entity, interaction, base, base_border, mut background, mut border) in &mut buttons {
+        let focused = focus.0 == Some(entity);
+        let fill = match interaction {
+            Interaction::Pressed => Role::ButtonPressed,
+            Interaction::Hovered => Role::ButtonHover,
+            Interaction::None if focused => Role::ButtonHover,
+            Interaction::None => base.0,
+        };
+        let outline = if focused {
+            Role::FocusBorder
+        } else {
+            base_border.0
+        };
+        background.set_if_neq(BackgroundColor(theme.color(fill)));
+        border.set_if_neq(BorderColor::all(theme.color(outline)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Affine2;
+
+    use super::*;
+
+    fn button(app: &mut App, x: f32, y: f32) -> Entity {
+        app.world_mut()
+            .spawn((
+                Button,
+                Interaction::None,
+                UiGlobalTransform::from(Affine2::from_translation(Vec2::new(x, y))),
+            ))
+            .id()
+    }
+
+    fn nav_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(ButtonInput::<KeyCode>::default());
+        app.init_resource::<Focus>();
+        app.add_systems(Update, navigate_buttons);
+        app
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.clear();
+        keys.release(key);
+        keys.press(key);
+        app.update();
+    }
+
+    fn focused(app: &App) -> Option<Entity> {
+        app.world().resource::<Focus>().0
+    }
+
+    #[test]
+    fn arrows_move_to_the_nearest_button_in_line() {
+        let mut app = nav_app();
+        let top = button(&mut app, 100.0, 0.0);
+        let middle = button(&mut app, 100.0, 50.0);
+        let left = button(&mut app, 0.0, 50.0);
+        let bottom = button(&mut app, 100.0, 100.0);
+
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focused(&app), Some(top));
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focused(&app), Some(middle));
+        press(&mut app, KeyCode::ArrowLeft);
+        assert_eq!(focused(&app), Some(left));
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focused(&app), Some(bottom));
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focused(&app), Some(bottom));
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(focused(&app), Some(top));
+    }
+
+    #[test]
+    fn enter_presses_the_focus_and_escape_the_back_button() {
+        let mut app = nav_app();
+        let start = button(&mut app, 0.0, 0.0);
+        let back = button(&mut app, 0.0, 50.0);
+        app.world_mut().entity_mut(back).insert(BackButton);
+        let interaction = |app: &App, entity| *app.world().get::<Interaction>(entity).unwrap();
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(interaction(&app, start), Interaction::None);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(interaction(&app, start), Interaction::Pressed);
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+        assert_eq!(interaction(&app, start), Interaction::None);
+
+        press(&mut app, KeyCode::Escape);
+        assert_eq!(interaction(&app, back), Interaction::Pressed);
+        assert_eq!(focused(&app), Some(back));
+    }
+
+    #[test]
+    fn dialogs_keep_the_focus_to_themselves() {
+        let mut app = nav_app();
+        button(&mut app, 0.0, 0.0);
+        let no = button(&mut app, 0.0, 50.0);
+        app.world_mut().entity_mut(no).insert(ModalButton::No);
+
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focused(&app), Some(no));
+        press(&mut app, KeyCode::ArrowUp);
+        assert_eq!(focused(&app), Some(no));
+    }
+}
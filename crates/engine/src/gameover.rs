@@ -1,7 +1,13 @@
 use bevy::prelude::*;
 use beavy_config as config;
 
-use crate::state::{AppState, GameResource};
+use crate::bindings::{Action, KeyBindings};
+use crate::focus::BackButton;
+use crate::modal::{open_modal, Confirm};
+use crate::rewind::Rewind;
+use crate::state::{AppState, ConfigResource, GameResource};
+use crate::theme::{Role, Theme};
+use crate::widgets::{spawn_screen, Pressed};
 
 #[derive(Component)]
 pub(crate) struct GameOverRoot;
@@ -9,129 +15,74 @@ pub(crate) struct GameOverRoot;
 #[derive(Component, Copy, Clone)]
 pub(crate) enum GameOverAction {
     Restart,
+    Menu,
     Exit,
 }
 
-pub(crate) fn setup_game_over(mut commands: Commands, state: Res<GameResource>) {
-    commands
-        .spawn((
-            Node {
-                width: percent(config::ui::ROOT_PERCENT),
-                height: percent(config::ui::ROOT_PERCENT),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                row_gap: px(config::ui::PANEL_GAP),
-                ..default()
-            },
-            BackgroundColor(color(config::colors::GAME_OVER_BG)),
-            GameOverRoot,
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                Text::new(config::text::GAME_OVER_TITLE),
-                TextFont {
-                    font_size: config::ui::TITLE_FONT_SIZE,
-                    ..default()
-                },
-                TextColor(color(config::colors::WHITE)),
-                GameOverRoot,
-            ));
-
-            parent.spawn((
-                Text::new(format!("{}{}", config::text::SCORE_LABEL, state.0.score)),
-                TextFont {
-                    font_size: config::ui::SUBTITLE_FONT_SIZE,
-                    ..default()
-                },
-                TextColor(color(config::colors::GAME_OVER_TEXT)),
-                GameOverRoot,
-            ));
-
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: px(config::ui::BUTTON_WIDTH),
-                        height: px(config::ui::BUTTON_HEIGHT),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        border: UiRect::all(px(config::ui::BUTTON_BORDER)),
-                        ..default()
-                    },
-                    BorderColor::all(color(config::colors::GAME_OVER_BUTTON_BORDER)),
-                    BackgroundColor(color(config::colors::GAME_OVER_BUTTON_BG)),
-                    GameOverAction::Restart,
-                    GameOverRoot,
-                ))
-                .with_children(|button| {
-                    button.spawn((
-                        Text::new(config::text::GAME_OVER_RESTART),
-                        TextFont {
-                            font_size: config::ui::BUTTON_FONT_SIZE,
-                            ..default()
-                        },
-                        TextColor(color(config::colors::WHITE)),
-                        GameOverRoot,
-                    ));
-                });
-
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: px(config::ui::BUTTON_WIDTH),
-                        height: px(config::ui::BUTTON_HEIGHT),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        border: UiRect::all(px(config::ui::BUTTON_BORDER)),
-                        ..default()
-                    },
-                    BorderColor::all(color(config::colors::GAME_OVER_BUTTON_BORDER)),
-                    BackgroundColor(color(config::colors::GAME_OVER_EXIT_BG)),
-                    GameOverAction::Exit,
-                    GameOverRoot,
-                ))
-                .with_children(|button| {
-                    button.spawn((
-                        Text::new(config::text::GAME_OVER_EXIT),
-                        TextFont {
-                            font_size: config::ui::BUTTON_FONT_SIZE,
-                            ..default()
-                        },
-                        TextColor(color(config::colors::WHITE)),
-                        GameOverRoot,
-                    ));
-                });
-        });
+pub(crate) fn setup_game_over(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    state: Res<GameResource>,
+    rewind: Option<Res<Rewind>>,
+) {
+    let config = &config.0;
+    let assisted = if rewind.is_some_and(|rewind| rewind.assisted) {
+        config.text.assisted_suffix.as_str()
+    } else {
+        ""
+    };
+    let text = &config.text;
+    let score = format!(
+        "{}{}{}",
+        text.score_label,
+        text.format_number(state.0.score.into()),
+        assisted
+    );
+    let screen = spawn_screen(
+        &mut commands,
+        config,
+        &theme,
+        Role::GameOverBg,
+        GameOverRoot,
+        |ui| {
+            ui.title(&text.game_over_title);
+            ui.text(score, config.ui.subtitle_font_size, Role::GameOverText);
+            ui.button_roles(Role::GameOverButtonBg, Role::GameOverButtonBorder);
+            ui.button(&text.game_over_restart, GameOverAction::Restart);
+            ui.button(&text.game_over_menu, GameOverAction::Menu)
+                .insert(BackButton);
+            let exit_background = ui.theme.background(Role::GameOverExitBg);
+            ui.button(&text.game_over_exit, GameOverAction::Exit)
+                .insert(exit_background);
+        },
+    );
+    // The board is left in place underneath, so the crash can still be seen.
+    let dimmed = theme
+        .color(Role::GameOverBg)
+        .with_alpha(config::ui::GAME_OVER_ALPHA);
+    commands.entity(screen).insert(BackgroundColor(dimmed));
 }
 
 pub(crate) fn game_over_input(
-    mut interactions: Query<(&Interaction, &GameOverAction), Changed<Interaction>>,
+    mut commands: Commands,
+    mut pressed: MessageReader<Pressed<GameOverAction>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut exit: MessageWriter<AppExit>,
 ) {
-    for (interaction, action) in &mut interactions {
-        if *interaction == Interaction::Pressed {
-            match action {
-                GameOverAction::Restart => next_state.set(AppState::Playing),
-                GameOverAction::Exit => {
-                    let _ = exit.write(AppExit::Success);
-                }
+    if bindings.just_pressed(&keys, Action::Restart) {
+        next_state.set(AppState::Playing);
+    }
+    for Pressed(action) in pressed.read() {
+        match action {
+            GameOverAction::Restart => next_state.set(AppState::Playing),
+            GameOverAction::Menu => next_state.set(AppState::Menu),
+            GameOverAction::Exit => {
+                open_modal(&mut commands, &config.0, &theme, Confirm::Exit);
             }
         }
     }
 }
-
-pub(crate) fn cleanup_game_over(
-    mut commands: Commands,
-    entities: Query<Entity, (With<GameOverRoot>, Without<ChildOf>)>,
-) {
-    for entity in &entities {
-        commands.entity(entity).despawn();
-    }
-}
-
-fn color(rgb: (f32, f32, f32)) -> Color {
-    Color::srgb(rgb.0, rgb.1, rgb.2)
-}
@@ -0,0 +1,201 @@
+//! Gamepads steering the snake, with the d-pad or the left stick. Pads are
+//! given to players in the order they connect. A pad that disconnects keeps
+//! its player, so plugging it back in gives it the same one; until then a
+//! newly connected pad can take its place. There is a single snake, so
+//! player one steers it, while every pad can still work the menus and pause
+//! the game.
+
+use bevy::prelude::*;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use beavy_config as config;
+
+const DPAD: [(GamepadButton, game::Direction); 4] = [
+    (GamepadButton::DPadUp, game::Direction::Up),
+    (GamepadButton::DPadDown, game::Direction::Down),
+    (GamepadButton::DPadLeft, game::Direction::Left),
+    (GamepadButton::DPadRight, game::Direction::Right),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct PadPlayer {
+    gamepad: Entity,
+    connected: bool,
+    /// Where the stick pointed last frame, so holding it turns only once.
+    stick: Option<game::Direction>,
+}
+
+/// Each player's gamepad, player one first.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct GamepadPlayers(Vec<PadPlayer>);
+
+impl GamepadPlayers {
+    /// Gives `gamepad` back to its player, or to the first player without
+    /// a connected pad, or to a new player.
+    fn connect(&mut self, gamepad: Entity) {
+        let player = PadPlayer {
+            gamepad,
+            connected: true,
+            stick: None,
+        };
+        let players = &mut self.0;
+        match players
+            .iter()
+            .position(|player| player.gamepad == gamepad)
+            .or_else(|| players.iter().position(|player| !player.connected))
+        {
+            Some(index) => players[index] = player,
+            None => players.push(player),
+        }
+    }
+
+    fn disconnect(&mut self, gamepad: Entity) {
+        for player in &mut self.0 {
+            if player.gamepad == gamepad {
+                player.connected = false;
+            }
+        }
+    }
+
+    /// Whether `gamepad` is player one's, connected or not.
+    pub(crate) fn is_player_one(&self, gamepad: Entity) -> bool {
+        self.0
+            .first()
+            .is_some_and(|player| player.gamepad == gamepad)
+    }
+
+    /// The turns player one asked for this frame: d-pad presses, and the
+    /// left stick being pushed in a new direction.
+    pub(crate) fn turns(&mut self, gamepads: &Query<&Gamepad>) -> Vec<game::Direction> {
+        let Some(player) = self.0.first_mut().filter(|player| player.connected) else {
+            return Vec::new();
+        };
+        let Ok(gamepad) = gamepads.get(player.gamepad) else {
+            return Vec::new();
+        };
+        let mut turns: Vec<_> = DPAD
+            .into_iter()
+            .filter(|(button, _)| gamepad.just_pressed(*button))
+            .map(|(_, direction)| direction)
+            .collect();
+        let stick = stick_direction(gamepad.left_stick());
+        if stick != player.stick {
+            turns.extend(stick);
+            player.stick = stick;
+        }
+        turns
+    }
+}
+
+/// The way the stick is pushed along whichever axis it is pushed further,
+/// once it is past the dead zone.
+fn stick_direction(stick: Vec2) -> Option<game::Direction> {
+    if stick.length() < config::controls::STICK_DEADZONE {
+        return None;
+    }
+    let direction = if stick.x.abs() > stick.y.abs() {
+        if stick.x > 0.0 {
+            game::Direction::Right
+        } else {
+            game::Direction::Left
+        }
+    } else if stick.y > 0.0 {
+        game::Direction::Up
+    } else {
+        game::Direction::Down
+    };
+    Some(direction)
+}
+
+pub(crate) fn assign_gamepads(
+    mut connections: MessageReader<GamepadConnectionEvent>,
+    mut players: ResMut<GamepadPlayers>,
+) {
+    for event in connections.read() {
+        match event.connection {
+            GamepadConnection::Connected { .. } => players.connect(event.gamepad),
+            GamepadConnection::Disconnected => players.disconnect(event.gamepad),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::input::gamepad::GamepadInput;
+
+    use super::*;
+
+    fn turns(world: &mut World) -> Vec<game::Direction> {
+        world
+            .run_system_once(
+                |gamepads: Query<&Gamepad>, mut players: ResMut<GamepadPlayers>| {
+                    players.turns(&gamepads)
+                },
+            )
+            .expect("system runs")
+    }
+
+    #[test]
+    fn the_stick_turns_along_its_stronger_axis_past_the_dead_zone() {
+        assert_eq!(stick_direction(Vec2::new(0.2, 0.1)), None);
+        assert_eq!(
+            stick_direction(Vec2::new(0.7, -0.5)),
+            Some(game::Direction::Right)
+        );
+        assert_eq!(
+            stick_direction(Vec2::new(0.4, -0.8)),
+            Some(game::Direction::Down)
+        );
+    }
+
+    #[test]
+    fn players_keep_their_pads_across_reconnects() {
+        let mut world = World::new();
+        let [first, second, third] = [(); 3].map(|_| world.spawn_empty().id());
+        let mut players = GamepadPlayers::default();
+        players.connect(first);
+        players.connect(second);
+        players.disconnect(first);
+        assert!(players.is_player_one(first));
+
+        players.connect(first);
+        assert!(players.is_player_one(first));
+        players.disconnect(first);
+        players.connect(third);
+        assert!(players.is_player_one(third));
+        assert_eq!(players.0.len(), 2);
+    }
+
+    #[test]
+    fn player_one_turns_with_the_dpad_and_once_per_push_of_the_stick() {
+        let mut world = World::new();
+        let pad = world.spawn(Gamepad::default()).id();
+        let other = world.spawn(Gamepad::default()).id();
+        let mut players = GamepadPlayers::default();
+        players.connect(pad);
+        players.connect(other);
+        world.insert_resource(players);
+
+        let mut gamepad = world.get_mut::<Gamepad>(pad).expect("gamepad");
+        gamepad.digital_mut().press(GamepadButton::DPadUp);
+        gamepad
+            .analog_mut()
+            .set(GamepadInput::Axis(GamepadAxis::LeftStickX), -0.9);
+        world
+            .get_mut::<Gamepad>(other)
+            .expect("gamepad")
+            .digital_mut()
+            .press(GamepadButton::DPadDown);
+        assert_eq!(
+            turns(&mut world),
+            vec![game::Direction::Up, game::Direction::Left]
+        );
+
+        world
+            .get_mut::<Gamepad>(pad)
+            .expect("gamepad")
+            .digital_mut()
+            .clear();
+        assert_eq!(turns(&mut world), Vec::new());
+    }
+}
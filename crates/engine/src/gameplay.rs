@@ -1,15 +1,86 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
 use bevy::transform::components::GlobalTransform;
 use beavy_config as config;
 
-use crate::state::{AppState, GameResource};
+use crate::bindings::{Action, KeyBindings};
+use crate::gamepads::GamepadPlayers;
+use crate::launch::LaunchOptions;
+use crate::pause::ResumePaused;
+use crate::rewind::Rewind;
+use crate::save::{self, PendingLoad};
+use crate::skin::{segment_shape, SnakeSkin};
+use crate::state::{AppState, ConfigResource, GameMode, GameResource};
+use crate::theme::{Role, Theme};
 
-#[derive(Resource)]
-pub(crate) struct StepTimer(Timer);
+/// Turns pressed since the last tick, applied one per tick in `FixedUpdate`
+/// so quick successive presses are not lost.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct InputQueue(VecDeque<game::Direction>);
+
+impl InputQueue {
+    fn push(&mut self, direction: game::Direction) {
+        if self.0.len() < config::timing::INPUT_QUEUE_LENGTH && self.0.back() != Some(&direction) {
+            self.0.push_back(direction);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+}
 
+/// One cell of the snake. It is drawn part of the way from `from` to `to`,
+/// following the time until the next tick, so the snake glides between them.
+/// `cell` is where it really is, which `to` is not while it wraps around.
 #[derive(Component)]
 pub(crate) struct SnakeSegment {
     index: usize,
+    from: Vec3,
+    to: Vec3,
+    cell: Vec3,
+}
+
+impl SnakeSegment {
+    pub(crate) fn at(index: usize, position: Vec3) -> Self {
+        Self {
+            index,
+            from: position,
+            to: position,
+            cell: position,
+        }
+    }
+
+    /// Moves to `position`, gliding there from the current cell when `glide`
+    /// is set and the two are neighbours on a `board` of that size. A move
+    /// that wraps around the board glides on to the cell just past the edge
+    /// instead, and the segment shows on `position` once the tick is over.
+    /// Rewinds and restarts jump straight to the new cell.
+    fn move_to(&mut self, position: Vec3, cell_size: f32, board: Vec2, glide: bool) {
+        let step = position - self.cell;
+        let step = Vec3::new(
+            wrap_step(step.x, board.x),
+            wrap_step(step.y, board.y),
+            step.z,
+        );
+        (self.from, self.to) = if glide && step.length() <= cell_size * 1.01 {
+            (self.cell, self.cell + step)
+        } else {
+            (position, position)
+        };
+        self.cell = position;
+    }
+}
+
+/// A move of `step` across a board `size` long, the short way round: a step
+/// across more than half the board is one that wrapped.
+fn wrap_step(step: f32, size: f32) -> f32 {
+    if step.abs() > size / 2.0 {
+        step - size * step.signum()
+    } else {
+        step
+    }
 }
 
 #[derive(Component)]
@@ -21,65 +92,364 @@ pub(crate) struct ScoreText;
 #[derive(Component)]
 pub(crate) struct ScoreRoot;
 
+/// One side of the border, indexing [`border_layout`].
+#[derive(Component)]
+pub(crate) struct BorderSegment(pub(crate) usize);
+
+#[derive(Component)]
+pub(crate) struct ObstacleSprite;
+
+/// What happened on a tick, for effects that follow the game.
+#[derive(Message, Debug, Copy, Clone, PartialEq)]
+pub(crate) enum GameEvent {
+    /// The head reached the food on `cell`, scoring `points`.
+    Ate {
+        cell: game::GridPos,
+        points: u32,
+    },
+    /// The snake set off in a new direction.
+    Turned,
+    Died,
+}
+
+/// Counts down from the snake's death to the game-over screen, so the
+/// crash can be seen.
+#[derive(Resource)]
+pub(crate) struct Dying(Timer);
+
+impl Dying {
+    pub(crate) fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+
+    /// How far through, from 0 at the crash to 1 at the game-over screen.
+    pub(crate) fn progress(&self) -> f32 {
+        self.0.fraction()
+    }
+
+    pub(crate) fn remaining_secs(&self) -> f32 {
+        self.0.remaining_secs()
+    }
+}
+
+/// Marks the cell the snake crashed into.
 #[derive(Component)]
-pub(crate) struct BorderSegment;
+pub(crate) struct CrashMarker;
+
+/// A replay from the command line, fed to the game in place of player input.
+#[derive(Resource)]
+pub(crate) struct ReplayPlayback(pub(crate) game::Replay);
 
-type GameplayCleanupQuery =
-    Or<(With<SnakeSegment>, With<FoodSprite>, With<ScoreRoot>, With<BorderSegment>)>;
-pub(crate) fn setup_gameplay(mut commands: Commands) {
-    let state = game::new_game(config::grid::WIDTH, config::grid::HEIGHT);
-    let state_snapshot = state.clone();
+type SegmentItem = (
+    Entity,
+    &'static mut SnakeSegment,
+    &'static mut Sprite,
+    &'static mut Transform,
+);
+
+type GameplayCleanupQuery = Or<(
+    With<SnakeSegment>,
+    With<FoodSprite>,
+    With<ScoreRoot>,
+    With<BorderSegment>,
+    With<ObstacleSprite>,
+    With<CrashMarker>,
+)>;
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn setup_gameplay(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    mode: Res<GameMode>,
+    launch: Res<LaunchOptions>,
+    pending: Option<Res<PendingLoad>>,
+    resume: Option<Res<ResumePaused>>,
+    mut fixed: ResMut<Time<Fixed>>,
+    skin: Res<SnakeSkin>,
+) {
+    let config = &config.0;
+    // A game coming back from the settings screen keeps its speed; a new
+    // speed picked there waits for the next game.
+    if resume.is_none() {
+        set_tick_rate(&mut fixed, &config.timing);
+    }
+    // A new or resumed game gets a whole tick before its first step.
+    let overstep = fixed.overstep();
+    fixed.discard_overstep(overstep);
+    let mut rewind = Rewind::new(*mode, &config.timing);
+    let state = match pending {
+        Some(pending) => {
+            commands.remove_resource::<PendingLoad>();
+            rewind.assisted = pending.0.assisted;
+            pending.0.state.clone()
+        }
+        None => {
+            // Watching a replay leaves the player's own save alone.
+            if launch.replay.is_none() {
+                save::delete_save();
+            }
+            launch.new_game(config, *mode)
+        }
+    };
+    match &launch.replay {
+        Some(replay) => commands.insert_resource(ReplayPlayback(replay.clone())),
+        None => commands.remove_resource::<ReplayPlayback>(),
+    }
+    spawn_board(&mut commands, config, &theme, &skin, &state);
     commands.insert_resource(GameResource(state));
-    commands.insert_resource(StepTimer(Timer::from_seconds(
-        config::timing::TICK_SECONDS,
-        TimerMode::Repeating,
-    )));
+    commands.insert_resource(InputQueue::default());
+    commands.insert_resource(rewind);
+}
+
+/// Runs `FixedUpdate`, and with it the game, once per configured tick.
+pub(crate) fn set_tick_rate(fixed: &mut Time<Fixed>, timing: &config::TimingConfig) {
+    fixed.set_timestep_seconds(timing.tick_seconds.into());
+}
+
+/// Replaces the board's sprites and score, e.g. after the cell size or the
+/// level's walls change.
+#[cfg(feature = "hot-reload")]
+pub(crate) fn rebuild_board(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    skin: Res<SnakeSkin>,
+    state: Res<GameResource>,
+    entities: Query<Entity, GameplayCleanupQuery>,
+) {
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+    spawn_board(&mut commands, &config.0, &theme, &skin, &state.0);
+}
+
+/// Queues the turns pressed this frame, on the keyboard or player one's
+/// gamepad, for the next ticks.
+pub(crate) fn handle_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    gamepads: Query<&Gamepad>,
+    mut players: ResMut<GamepadPlayers>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut queue: ResMut<InputQueue>,
+) {
+    if playback.is_some() {
+        return;
+    }
+    let turns = [
+        (Action::TurnUp, game::Direction::Up),
+        (Action::TurnDown, game::Direction::Down),
+        (Action::TurnLeft, game::Direction::Left),
+        (Action::TurnRight, game::Direction::Right),
+    ];
+    for (action, direction) in turns {
+        if bindings.just_pressed(&keys, action) {
+            queue.push(direction);
+        }
+    }
+    for direction in players.turns(&gamepads) {
+        queue.push(direction);
+    }
+}
 
-    spawn_borders(&mut commands, &state_snapshot);
-    spawn_snake(&mut commands, &state_snapshot);
-    spawn_food(&mut commands, &state_snapshot);
-    spawn_score(&mut commands);
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn restart_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    config: Res<ConfigResource>,
+    mode: Res<GameMode>,
+    launch: Res<LaunchOptions>,
+    mut game: (ResMut<GameResource>, ResMut<Rewind>, ResMut<InputQueue>),
+    mut fixed: ResMut<Time<Fixed>>,
+) {
+    if bindings.just_pressed(&keys, Action::Restart) {
+        let (state, rewind, queue) = &mut game;
+        restart(&config.0, *mode, &launch, state, rewind, queue, &mut fixed);
+    }
 }
 
-pub(crate) fn handle_input(keys: Res<ButtonInput<KeyCode>>, mut state: ResMut<GameResource>) {
-    if keys.just_pressed(KeyCode::ArrowUp) || keys.just_pressed(KeyCode::KeyW) {
-        game::set_direction(&mut state.0, game::Direction::Up);
-    } else if keys.just_pressed(KeyCode::ArrowDown) || keys.just_pressed(KeyCode::KeyS) {
-        game::set_direction(&mut state.0, game::Direction::Down);
-    } else if keys.just_pressed(KeyCode::ArrowLeft) || keys.just_pressed(KeyCode::KeyA) {
-        game::set_direction(&mut state.0, game::Direction::Left);
-    } else if keys.just_pressed(KeyCode::ArrowRight) || keys.just_pressed(KeyCode::KeyD) {
-        game::set_direction(&mut state.0, game::Direction::Right);
+/// Starts a new game in place; re-entering `AppState::Playing` from itself
+/// would not run the setup systems again.
+pub(crate) fn restart(
+    config: &config::Config,
+    mode: GameMode,
+    launch: &LaunchOptions,
+    state: &mut GameResource,
+    rewind: &mut Rewind,
+    queue: &mut InputQueue,
+    fixed: &mut Time<Fixed>,
+) {
+    if launch.replay.is_none() {
+        save::delete_save();
     }
+    state.0 = launch.new_game(config, mode);
+    *rewind = Rewind::new(mode, &config.timing);
+    queue.0.clear();
+    fixed.discard_overstep(fixed.overstep());
 }
 
-#[allow(clippy::type_complexity)]
+/// Steps the game once. Runs in `FixedUpdate`, so a slow frame runs as
+/// many steps as the time it took calls for.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn advance_game(
-    time: Res<Time>,
-    mut timer: ResMut<StepTimer>,
+    mut commands: Commands,
+    config: Res<ConfigResource>,
     mut state: ResMut<GameResource>,
+    mut rewind: ResMut<Rewind>,
+    mut queue: ResMut<InputQueue>,
+    playback: Option<Res<ReplayPlayback>>,
+    dying: Option<Res<Dying>>,
+    mut events: MessageWriter<GameEvent>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut commands: Commands,
-    mut transforms: ParamSet<(
-        Query<(Entity, &mut SnakeSegment, &mut Transform)>,
-        Query<&mut Transform, With<FoodSprite>>,
-    )>,
-    mut score_text: Query<&mut TextSpan, With<ScoreText>>,
 ) {
-    if !timer.0.tick(time.delta()).just_finished() {
+    // Further ticks in the frame that ended the game must not move it on.
+    if matches!(*next_state, NextState::Pending(_)) || dying.is_some() {
         return;
     }
 
+    rewind.record(&state.0);
+    match &playback {
+        Some(playback) => {
+            let tick = state.0.tick;
+            for input in playback.0.inputs.iter().filter(|input| input.tick == tick) {
+                game::set_direction(&mut state.0, input.direction);
+            }
+        }
+        None => {
+            if let Some(direction) = queue.0.pop_front() {
+                game::set_direction(&mut state.0, direction);
+            }
+        }
+    }
+    let (score, direction) = (state.0.score, state.0.direction);
     let result = game::step(&mut state.0);
-    sync_snake(&mut commands, &state.0, &mut transforms.p0());
-    sync_food(&state.0, &mut transforms.p1());
-    update_score(&state.0, &mut score_text);
 
-    if result == game::StepResult::GameOver {
+    if state.0.direction != direction {
+        events.write(GameEvent::Turned);
+    }
+    match result {
+        game::StepResult::Ate => {
+            events.write(GameEvent::Ate {
+                cell: state.0.snake[0],
+                points: state.0.score - score,
+            });
+        }
+        game::StepResult::GameOver => {
+            events.write(GameEvent::Died);
+            commands.insert_resource(Dying::new(config.0.timing.death_seconds));
+            return;
+        }
+        game::StepResult::Moved => {}
+    }
+    if playback.is_some_and(|playback| state.0.tick >= playback.0.ticks) {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+/// Plays out the snake's death: marks the cell it crashed into and flashes
+/// it, then shows the game-over screen over the board. A rewind or restart
+/// in the meantime brings the snake back and calls it off.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn play_death(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    dying: Option<ResMut<Dying>>,
+    state: Res<GameResource>,
+    mut segments: Query<(&mut Sprite, &mut Visibility), With<SnakeSegment>>,
+    markers: Query<Entity, With<CrashMarker>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(mut dying) = dying else {
+        return;
+    };
+    if state.0.alive {
+        commands.remove_resource::<Dying>();
+        for entity in &markers {
+            commands.entity(entity).despawn();
+        }
+        for (mut sprite, mut visibility) in &mut segments {
+            sprite.color = theme.color(Role::Snake);
+            *visibility = Visibility::Visible;
+        }
+        return;
+    }
+    if dying.is_added()
+        && let Some(cell) = game::crash_cell(&state.0)
+    {
+        spawn_crash_marker(&mut commands, &config.0, &theme, &state.0, cell);
+    }
+
+    let elapsed = dying.0.tick(time.delta()).elapsed_secs();
+    let flashed = (elapsed / config::effects::FLASH_SECONDS) as u32 % 2 == 1;
+    let role = if flashed && !dying.0.is_finished() {
+        Role::Crash
+    } else {
+        Role::Snake
+    };
+    for (mut sprite, _) in &mut segments {
+        sprite.color = theme.color(role);
+    }
+    if dying.0.is_finished() {
+        commands.remove_resource::<Dying>();
         next_state.set(AppState::GameOver);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sync_board(
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    skin: Res<SnakeSkin>,
+    state: Res<GameResource>,
+    mut commands: Commands,
+    mut segments: Query<SegmentItem>,
+    mut food: Query<&mut Transform, With<FoodSprite>>,
+    mut score_text: Query<&mut TextSpan, With<ScoreText>>,
+    mut last_tick: Local<Option<u64>>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+
+    // Only a single step forwards glides; anything else is a jump.
+    let glide = last_tick.is_some_and(|tick| tick + 1 == state.0.tick);
+    *last_tick = Some(state.0.tick);
+    sync_snake(
+        &mut commands,
+        &config.0,
+        &theme,
+        &skin,
+        &state.0,
+        &mut segments,
+        glide,
+    );
+    sync_food(&config.0, &state.0, &mut food);
+    update_score(&config.0.text, &state.0, &mut score_text);
+}
+
+/// Places each segment between its previous and current cell by how far
+/// the next tick has come, or on its current cell with smooth movement off.
+pub(crate) fn glide_snake(
+    config: Res<ConfigResource>,
+    fixed: Res<Time<Fixed>>,
+    mut segments: Query<(&SnakeSegment, &mut Transform)>,
+) {
+    let progress = if config.0.display.smooth_movement {
+        fixed.overstep_fraction().min(1.0)
+    } else {
+        1.0
+    };
+    for (segment, mut transform) in &mut segments {
+        transform.translation = if progress < 1.0 {
+            segment.from.lerp(segment.to, progress)
+        } else {
+            segment.cell
+        };
+    }
+}
+
 pub(crate) fn cleanup_gameplay(
     mut commands: Commands,
     entities: Query<Entity, GameplayCleanupQuery>,
@@ -87,38 +457,100 @@ pub(crate) fn cleanup_gameplay(
     for entity in &entities {
         commands.entity(entity).despawn();
     }
+    commands.remove_resource::<Dying>();
 }
 
-fn spawn_snake(commands: &mut Commands, state: &game::GameState) {
-    for (index, segment) in state.snake.iter().enumerate() {
-        commands.spawn((
-            Sprite {
-                color: color(config::colors::SNAKE),
-                custom_size: Some(Vec2::splat(config::grid::SNAKE_SIZE)),
-                ..default()
-            },
-            Transform::from_translation(grid_to_world(
-                state.grid_width,
-                state.grid_height,
-                *segment,
-            )),
-            GlobalTransform::default(),
-            Visibility::Visible,
-            InheritedVisibility::default(),
-            ViewVisibility::default(),
-            SnakeSegment { index },
-        ));
+fn spawn_board(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    skin: &SnakeSkin,
+    state: &game::GameState,
+) {
+    spawn_borders(commands, config, theme, state);
+    spawn_obstacles(commands, config, theme, state);
+    spawn_snake(commands, config, theme, skin, state);
+    spawn_food(commands, config, theme, state);
+    spawn_score(commands, config, theme, state);
+}
+
+fn spawn_snake(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    skin: &SnakeSkin,
+    state: &game::GameState,
+) {
+    for index in 0..state.snake.len() {
+        spawn_segment(commands, config, theme, skin, state, index);
     }
 }
 
-fn spawn_food(commands: &mut Commands, state: &game::GameState) {
+/// Spawns segment `index` of the snake on its cell.
+fn spawn_segment(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    skin: &SnakeSkin,
+    state: &game::GameState,
+    index: usize,
+) {
+    let position = grid_to_world(
+        config.grid.cell_size,
+        state.grid_width,
+        state.grid_height,
+        state.snake[index],
+    );
+    // The skin's frames fill the cell so neighbouring segments join up.
+    let (mut sprite, color) = theme.sprite(Role::Snake, Vec2::splat(config.grid.cell_size));
+    let (shape, angle) = segment_shape(state, index);
+    skin.apply(&mut sprite, shape);
+    commands.spawn((
+        sprite,
+        color,
+        Transform::from_translation(position).with_rotation(Quat::from_rotation_z(angle)),
+        GlobalTransform::default(),
+        Visibility::Visible,
+        InheritedVisibility::default(),
+        ViewVisibility::default(),
+        SnakeSegment::at(index, position),
+    ));
+}
+
+fn spawn_crash_marker(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    state: &game::GameState,
+    cell: game::GridPos,
+) {
+    let position = grid_to_world(
+        config.grid.cell_size,
+        state.grid_width,
+        state.grid_height,
+        cell,
+    );
+    commands.spawn((
+        theme.sprite(Role::Crash, Vec2::splat(config.grid.cell_size)),
+        Transform::from_translation(position.with_z(config::grid::CRASH_Z)),
+        GlobalTransform::default(),
+        Visibility::Visible,
+        InheritedVisibility::default(),
+        ViewVisibility::default(),
+        CrashMarker,
+    ));
+}
+
+fn spawn_food(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    state: &game::GameState,
+) {
     commands.spawn((
-        Sprite {
-            color: color(config::colors::FOOD),
-            custom_size: Some(Vec2::splat(config::grid::FOOD_SIZE)),
-            ..default()
-        },
+        theme.sprite(Role::Food, Vec2::splat(config.grid.food_size)),
         Transform::from_translation(grid_to_world(
+            config.grid.cell_size,
             state.grid_width,
             state.grid_height,
             state.food,
@@ -131,42 +563,72 @@ fn spawn_food(commands: &mut Commands, state: &game::GameState) {
     ));
 }
 
-fn spawn_score(commands: &mut Commands) {
+fn spawn_score(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    state: &game::GameState,
+) {
     commands
         .spawn((
-            Text::new(config::text::SCORE_LABEL),
+            Text::new(config.text.score_label.as_str()),
             TextFont {
-                font_size: config::ui::SCORE_FONT_SIZE,
+                font_size: config.ui.score_font_size,
                 ..default()
             },
             Node {
                 position_type: PositionType::Absolute,
-                top: px(config::ui::SCORE_TOP),
-                right: px(config::ui::SCORE_RIGHT),
+                top: px(config.ui.score_top),
+                right: px(config.ui.score_right),
                 ..default()
             },
+            theme.text(Role::Text),
             ScoreRoot,
         ))
         .with_child((
-            TextSpan::new("0"),
+            TextSpan::new(config.text.format_number(state.score.into())),
             TextFont {
-                font_size: config::ui::SCORE_FONT_SIZE,
+                font_size: config.ui.score_font_size,
                 ..default()
             },
-            TextColor(color(config::colors::WHITE)),
+            theme.text(Role::Text),
             ScoreText,
         ));
 }
 
-fn spawn_borders(commands: &mut Commands, state: &game::GameState) {
-    let width = state.grid_width as f32 * config::grid::CELL_SIZE;
-    let height = state.grid_height as f32 * config::grid::CELL_SIZE;
+fn spawn_borders(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    state: &game::GameState,
+) {
+    let board = board_size(config.grid.cell_size, state);
+    let layout = border_layout(board, config.grid.border_thickness);
+    for (side, (size, translation)) in layout.into_iter().enumerate() {
+        commands.spawn((
+            theme.sprite(Role::Border, size),
+            Transform::from_translation(translation),
+            GlobalTransform::default(),
+            Visibility::Visible,
+            InheritedVisibility::default(),
+            ViewVisibility::default(),
+            BorderSegment(side),
+        ));
+    }
+}
+
+/// The width and height of the board inside its borders.
+pub(crate) fn board_size(cell_size: f32, state: &game::GameState) -> Vec2 {
+    IVec2::new(state.grid_width, state.grid_height).as_vec2() * cell_size
+}
+
+/// The size and position of each side of a border `thickness` thick around
+/// a board of `board` size: top, bottom, left and right.
+pub(crate) fn border_layout(board: Vec2, thickness: f32) -> [(Vec2, Vec3); 4] {
+    let (width, height) = (board.x, board.y);
     let half_width = width / 2.0;
     let half_height = height / 2.0;
-    let thickness = config::grid::BORDER_THICKNESS;
-    let color = color(config::colors::BORDER);
-
-    let segments = [
+    [
         (
             Vec2::new(width + thickness, thickness),
             Vec3::new(0.0, half_height + thickness / 2.0, config::grid::BORDER_Z),
@@ -183,91 +645,247 @@ fn spawn_borders(commands: &mut Commands, state: &game::GameState) {
             Vec2::new(thickness, height + thickness),
             Vec3::new(half_width + thickness / 2.0, 0.0, config::grid::BORDER_Z),
         ),
-    ];
+    ]
+}
 
-    for (size, translation) in segments {
+fn spawn_obstacles(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    state: &game::GameState,
+) {
+    for obstacle in &state.rules.obstacles {
         commands.spawn((
-            Sprite {
-                color,
-                custom_size: Some(size),
-                ..default()
-            },
-            Transform::from_translation(translation),
+            theme.sprite(Role::Border, Vec2::splat(config.grid.cell_size)),
+            Transform::from_translation(grid_to_world(
+                config.grid.cell_size,
+                state.grid_width,
+                state.grid_height,
+                *obstacle,
+            )),
             GlobalTransform::default(),
             Visibility::Visible,
             InheritedVisibility::default(),
             ViewVisibility::default(),
-            BorderSegment,
+            ObstacleSprite,
         ));
     }
 }
 
 fn sync_snake(
     commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    skin: &SnakeSkin,
     state: &game::GameState,
-    segments: &mut Query<(Entity, &mut SnakeSegment, &mut Transform)>,
+    segments: &mut Query<SegmentItem>,
+    glide: bool,
 ) {
-    let mut items: Vec<(Entity, usize, Mut<Transform>)> = segments
-        .iter_mut()
-        .map(|(entity, segment, transform)| (entity, segment.index, transform))
-        .collect();
-    items.sort_by_key(|(_, index, _)| *index);
-
-    for (index, pos) in state.snake.iter().enumerate() {
-        if let Some((_, _, transform)) = items.iter_mut().find(|(_, idx, _)| *idx == index) {
-            transform.translation = grid_to_world(state.grid_width, state.grid_height, *pos);
-        } else {
-            commands.spawn((
-                Sprite {
-                    color: color(config::colors::SNAKE),
-                    custom_size: Some(Vec2::splat(config::grid::SNAKE_SIZE)),
-                    ..default()
-                },
-                Transform::from_translation(grid_to_world(
-                    state.grid_width,
-                    state.grid_height,
-                    *pos,
-                )),
-                GlobalTransform::default(),
-                Visibility::Visible,
-                InheritedVisibility::default(),
-                ViewVisibility::default(),
-                SnakeSegment { index },
-            ));
-        }
+    let mut seen = vec![false; state.snake.len()];
+    for (entity, mut segment, mut sprite, mut transform) in segments.iter_mut() {
+        let index = segment.index;
+        let Some(pos) = state.snake.get(index) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let position = grid_to_world(
+            config.grid.cell_size,
+            state.grid_width,
+            state.grid_height,
+            *pos,
+        );
+        let board = board_size(config.grid.cell_size, state);
+        segment.move_to(position, config.grid.cell_size, board, glide);
+        let (shape, angle) = segment_shape(state, index);
+        skin.apply(&mut sprite, shape);
+        transform.rotation = Quat::from_rotation_z(angle);
+        seen[index] = true;
     }
 
-    for (entity, index, _) in items {
-        if index >= state.snake.len() {
-            commands.entity(entity).despawn();
-        }
+    // A segment added by growing starts on the cell the old tail is leaving.
+    for (index, _) in seen.iter().enumerate().filter(|(_, seen)| !**seen) {
+        spawn_segment(commands, config, theme, skin, state, index);
     }
 }
 
-fn sync_food(state: &game::GameState, food: &mut Query<&mut Transform, With<FoodSprite>>) {
+fn sync_food(
+    config: &config::Config,
+    state: &game::GameState,
+    food: &mut Query<&mut Transform, With<FoodSprite>>,
+) {
     if let Ok(mut transform) = food.single_mut() {
-        transform.translation =
-            grid_to_world(state.grid_width, state.grid_height, state.food);
+        transform.translation = grid_to_world(
+            config.grid.cell_size,
+            state.grid_width,
+            state.grid_height,
+            state.food,
+        );
     }
 }
 
-fn update_score(state: &game::GameState, score_text: &mut Query<&mut TextSpan, With<ScoreText>>) {
+fn update_score(
+    text: &config::TextConfig,
+    state: &game::GameState,
+    score_text: &mut Query<&mut TextSpan, With<ScoreText>>,
+) {
     if let Ok(mut span) = score_text.single_mut() {
-        **span = state.score.to_string();
+        **span = text.format_number(state.score.into());
     }
 }
 
-fn grid_to_world(grid_width: i32, grid_height: i32, pos: game::GridPos) -> Vec3 {
+pub(crate) fn grid_to_world(
+    cell_size: f32,
+    grid_width: i32,
+    grid_height: i32,
+    pos: game::GridPos,
+) -> Vec3 {
     let offset_x = grid_width as f32 / 2.0 - 0.5;
     let offset_y = grid_height as f32 / 2.0 - 0.5;
 
     Vec3::new(
-        (pos.x as f32 - offset_x) * config::grid::CELL_SIZE,
-        (pos.y as f32 - offset_y) * config::grid::CELL_SIZE,
+        (pos.x as f32 - offset_x) * cell_size,
+        (pos.y as f32 - offset_y) * cell_size,
         0.0,
     )
 }
 
-fn color(rgb: (f32, f32, f32)) -> Color {
-    Color::srgb(rgb.0, rgb.1, rgb.2)
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::state::app::StatesPlugin;
+    use bevy::time::{TimePlugin, TimeUpdateStrategy};
+
+    use super::*;
+
+    const CELL: f32 = 24.0;
+    const BOARD: Vec2 = Vec2::new(20.0 * CELL, 15.0 * CELL);
+
+    fn game_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, StatesPlugin));
+        app.init_state::<AppState>();
+        app.insert_resource(GameResource(game::new_game(20, 20)));
+        app.insert_resource(Rewind::new(
+            GameMode::Normal,
+            &config::TimingConfig::default(),
+        ));
+        app.init_resource::<InputQueue>();
+        app.init_resource::<ConfigResource>();
+        app.add_message::<GameEvent>();
+        app
+    }
+
+    fn tick(app: &App) -> u64 {
+        app.world().resource::<GameResource>().0.tick
+    }
+
+    #[test]
+    fn a_slow_frame_runs_every_tick_it_covers() {
+        let mut app = game_app();
+        app.add_systems(FixedUpdate, advance_game);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            210,
+        )));
+        app.world_mut()
+            .resource_mut::<Time<Fixed>>()
+            .set_timestep(Duration::from_millis(40));
+
+        app.update();
+        assert_eq!(tick(&app), 0);
+        app.update();
+        assert_eq!(tick(&app), 5);
+    }
+
+    #[test]
+    fn queued_turns_apply_one_per_tick() {
+        let mut app = game_app();
+        let mut queue = app.world_mut().resource_mut::<InputQueue>();
+        for direction in [
+            game::Direction::Up,
+            game::Direction::Up,
+            game::Direction::Left,
+        ] {
+            queue.push(direction);
+        }
+        let direction = |app: &App| app.world().resource::<GameResource>().0.direction;
+
+        app.world_mut()
+            .run_system_cached(advance_game)
+            .expect("system runs");
+        assert_eq!(direction(&app), game::Direction::Up);
+        app.world_mut()
+            .run_system_cached(advance_game)
+            .expect("system runs");
+        assert_eq!(direction(&app), game::Direction::Left);
+    }
+
+    #[test]
+    fn the_game_over_screen_waits_for_the_death() {
+        let mut app = game_app();
+        app.add_systems(Update, play_death);
+        app.init_resource::<Theme>();
+        app.world_mut()
+            .resource_mut::<ConfigResource>()
+            .0
+            .timing
+            .death_seconds = 0.5;
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            200,
+        )));
+        // Heading right off the edge of the board.
+        let mut state = app.world_mut().resource_mut::<GameResource>();
+        state.0.snake = vec![game::GridPos { x: 19, y: 5 }];
+        state.0.food = game::GridPos { x: 0, y: 0 };
+        let app_state = |app: &App| app.world().resource::<State<AppState>>().get().clone();
+
+        app.world_mut()
+            .run_system_cached(advance_game)
+            .expect("system runs");
+        assert!(app.world().contains_resource::<Dying>());
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(app_state(&app), AppState::Menu);
+        let world = app.world_mut();
+        let marker = world
+            .query_filtered::<&Transform, With<CrashMarker>>()
+            .single(world)
+            .expect("one marker");
+        assert_eq!(marker.translation.x, 10.5 * CELL);
+        for _ in 0..4 {
+            app.update();
+        }
+        assert_eq!(app_state(&app), AppState::GameOver);
+    }
+
+    #[test]
+    fn segments_glide_to_neighbouring_cells() {
+        let mut segment = SnakeSegment::at(0, Vec3::ZERO);
+        segment.move_to(Vec3::new(CELL, 0.0, 0.0), CELL, BOARD, true);
+
+        assert_eq!(segment.from, Vec3::ZERO);
+        assert_eq!(segment.to, Vec3::new(CELL, 0.0, 0.0));
+    }
+
+    #[test]
+    fn wrapping_glides_past_the_edge_and_jumps_do_not_glide() {
+        let near_edge = Vec3::new(-9.5 * CELL, 0.0, 0.0);
+        let far_edge = Vec3::new(9.5 * CELL, 0.0, 0.0);
+        let mut segment = SnakeSegment::at(0, near_edge);
+        segment.move_to(far_edge, CELL, BOARD, true);
+        assert_eq!(segment.from, near_edge);
+        assert_eq!(segment.to, near_edge - Vec3::new(CELL, 0.0, 0.0));
+        assert_eq!(segment.cell, far_edge);
+
+        // The next tick carries on from the real cell.
+        let next = far_edge - Vec3::new(CELL, 0.0, 0.0);
+        segment.move_to(next, CELL, BOARD, true);
+        assert_eq!(segment.from, far_edge);
+
+        segment.move_to(Vec3::ZERO, CELL, BOARD, true);
+        assert_eq!(segment.from, Vec3::ZERO);
+        segment.move_to(Vec3::new(0.0, CELL, 0.0), CELL, BOARD, false);
+        assert_eq!(segment.from, segment.to);
+    }
 }
@@ -0,0 +1,388 @@
+//! Development builds (`--features hot-reload`) watch the config file, the
+//! user themes directory and the level file, and apply edits while the game
+//! runs. Edits that fail to load are shown on screen and the previous values
+//! stay in use until the file is fixed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use beavy_config as config;
+
+use crate::bindings::KeyBindings;
+use crate::gameplay::{rebuild_board, restart, set_tick_rate, InputQueue};
+use crate::hud::{cleanup_hud, setup_hud};
+use crate::launch::LaunchOptions;
+use crate::locale::Locales;
+use crate::rewind::Rewind;
+use crate::state::{AppState, ConfigResource, GameMode, GameResource};
+use crate::theme::{Role, Theme, Themes};
+
+pub(crate) struct HotReloadPlugin;
+
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReloadErrors>()
+            .add_systems(Startup, setup_hot_reload)
+            .add_systems(
+                Update,
+                (
+                    poll_files,
+                    show_reload_errors.run_if(resource_changed::<ReloadErrors>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Watched {
+    Config,
+    Themes,
+    Level,
+}
+
+/// Modification times of a file, or of every `*.toml` file in a directory.
+type Stamp = Vec<(PathBuf, SystemTime)>;
+
+#[derive(Resource)]
+struct WatchedFiles {
+    timer: Timer,
+    files: Vec<(Watched, PathBuf, Stamp)>,
+}
+
+/// The latest failed reload of each watched file.
+#[derive(Resource, Debug, Default)]
+struct ReloadErrors(Vec<(Watched, String)>);
+
+impl ReloadErrors {
+    fn set(&mut self, watched: Watched, error: Option<String>) {
+        self.0.retain(|(known, _)| *known != watched);
+        if let Some(error) = error {
+            warn!("{error}");
+            self.0.push((watched, error));
+        }
+    }
+}
+
+#[derive(Component)]
+struct ReloadErrorRoot;
+
+#[derive(Component)]
+struct ReloadErrorText;
+
+fn setup_hot_reload(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    launch: Res<LaunchOptions>,
+) {
+    let files = [
+        (Watched::Config, config::config_path()),
+        (Watched::Themes, config::themes::themes_dir()),
+        (Watched::Level, launch.level_path.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(watched, path)| path.map(|path| (watched, path)))
+    .map(|(watched, path)| {
+        let stamp = stamp(&path);
+        (watched, path, stamp)
+    })
+    .collect();
+    commands.insert_resource(WatchedFiles {
+        timer: Timer::from_seconds(
+            config::timing::HOT_RELOAD_POLL_SECONDS,
+            TimerMode::Repeating,
+        ),
+        files,
+    });
+
+    let config = &config.0;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: px(config::ui::RELOAD_ERROR_BOTTOM),
+                left: px(config::ui::RELOAD_ERROR_BOTTOM),
+                right: px(config::ui::RELOAD_ERROR_BOTTOM),
+                padding: UiRect::all(px(config::ui::RELOAD_ERROR_PADDING)),
+                ..default()
+            },
+            theme.background(Role::GameOverBg),
+            GlobalZIndex(config::ui::OVERLAY_Z_INDEX),
+            Visibility::Hidden,
+            ReloadErrorRoot,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont {
+                font_size: config.ui.hud_font_size,
+                ..default()
+            },
+            theme.text(Role::GameOverText),
+            ReloadErrorText,
+        ));
+}
+
+/// Every few frames, reloads whichever watched files have changed.
+fn poll_files(mut commands: Commands, time: Res<Time>, mut watched: ResMut<WatchedFiles>) {
+    if !watched.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for (kind, path, last) in &mut watched.files {
+        let current = stamp(path);
+        if current == *last {
+            continue;
+        }
+        *last = current;
+        info!("{} changed, reloading", path.display());
+        match kind {
+            Watched::Config => commands.run_system_cached(reload_config),
+            Watched::Themes => commands.run_system_cached(reload_themes),
+            Watched::Level => commands.run_system_cached(reload_level),
+        }
+    }
+}
+
+/// Applies the edited config file, keeping the command-line overrides. Tuning
+/// values take effect immediately and the board and HUD are laid out again.
+#[allow(clippy::too_many_arguments)]
+fn reload_config(
+    mut commands: Commands,
+    mut errors: ResMut<ReloadErrors>,
+    launch: Res<LaunchOptions>,
+    mut config: ResMut<ConfigResource>,
+    mut locales: ResMut<Locales>,
+    themes: Res<Themes>,
+    mut theme: ResMut<Theme>,
+    mut bindings: ResMut<KeyBindings>,
+    mut fixed: ResMut<Time<Fixed>>,
+    state: Res<State<AppState>>,
+) {
+    let mut loaded = match config::load().and_then(|loaded| launch.overriding(loaded)) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            errors.set(Watched::Config, Some(err.to_string()));
+            return;
+        }
+    };
+    errors.set(Watched::Config, None);
+
+    locales.set_english(&loaded.text);
+    loaded.text = locales.text(&loaded.display.language);
+    let (reloaded, unknown_keys) = KeyBindings::from_config(&loaded.controls);
+    for name in unknown_keys {
+        warn!("controls: unknown key {name:?} ignored");
+    }
+    *bindings = reloaded;
+    theme.set_if_neq(Theme::select(&loaded, &themes));
+    set_tick_rate(&mut fixed, &loaded.timing);
+    config.0 = loaded;
+
+    if *state.get() == AppState::Playing {
+        commands.run_system_cached(rebuild_board);
+        commands.run_system_cached(cleanup_hud);
+        commands.run_system_cached(setup_hud);
+    }
+}
+
+/// Reloads the user themes; sprites and screens recolour through [`Theme`].
+fn reload_themes(
+    mut errors: ResMut<ReloadErrors>,
+    config: Res<ConfigResource>,
+    mut themes: ResMut<Themes>,
+    mut theme: ResMut<Theme>,
+) {
+    let (loaded, failed) = config::themes::load_all();
+    *themes = Themes(loaded);
+    theme.set_if_neq(Theme::select(&config.0, &themes));
+    let failed: Vec<String> = failed.iter().map(ToString::to_string).collect();
+    errors.set(
+        Watched::Themes,
+        (!failed.is_empty()).then(|| failed.join("\n")),
+    );
+}
+
+/// Reloads the level file and, when playing, restarts on the new layout.
+#[allow(clippy::too_many_arguments)]
+fn reload_level(
+    mut commands: Commands,
+    mut errors: ResMut<ReloadErrors>,
+    mut launch: ResMut<LaunchOptions>,
+    mut config: ResMut<ConfigResource>,
+    mode: Res<GameMode>,
+    state: Res<State<AppState>>,
+    game: Option<ResMut<GameResource>>,
+    queue: Option<ResMut<InputQueue>>,
+    rewind: Option<ResMut<Rewind>>,
+    mut fixed: ResMut<Time<Fixed>>,
+) {
+    let Some(path) = launch.level_path.clone() else {
+        return;
+    };
+    let mut updated = launch.clone();
+    let result = read_level(&path).and_then(|level| {
+        updated.level = Some(level);
+        updated
+            .overriding(config.0.clone())
+            .map_err(|err| err.to_string())
+    });
+    let reloaded = match result {
+        Ok(reloaded) => reloaded,
+        Err(err) => {
+            errors.set(Watched::Level, Some(err));
+            return;
+        }
+    };
+    errors.set(Watched::Level, None);
+    *launch = updated;
+    config.0 = reloaded;
+
+    if *state.get() != AppState::Playing {
+        return;
+    }
+    if let (Some(mut game), Some(mut rewind), Some(mut queue)) = (game, rewind, queue) {
+        restart(
+            &config.0,
+            *mode,
+            &launch,
+            &mut game,
+            &mut rewind,
+            &mut queue,
+            &mut fixed,
+        );
+        commands.run_system_cached(rebuild_board);
+    }
+}
+
+fn read_level(path: &Path) -> Result<game::Level, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    game::Level::parse(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn show_reload_errors(
+    errors: Res<ReloadErrors>,
+    mut roots: Query<&mut Visibility, With<ReloadErrorRoot>>,
+    mut texts: Query<&mut Text, With<ReloadErrorText>>,
+) {
+    for mut visibility in &mut roots {
+        *visibility = if errors.0.is_empty() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+    for mut text in &mut texts {
+        **text = errors
+            .0
+            .iter()
+            .map(|(_, error)| error.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let modified = |path: PathBuf| {
+        let time = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+        Some((path, time))
+    };
+    if !path.is_dir() {
+        return modified(path.to_path_buf()).into_iter().collect();
+    }
+    let mut stamp: Stamp = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(modified)
+        .collect();
+    stamp.sort();
+    stamp
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("beavy-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp dir");
+        dir
+    }
+
+    #[test]
+    fn stamps_change_when_files_are_edited_added_or_removed() {
+        let dir = temp_dir("stamp");
+        let theme = dir.join("a.toml");
+        fs::write(&theme, "name = \"A\"\n").expect("write");
+        let first = stamp(&dir);
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&theme)
+            .and_then(|file| file.set_modified(later))
+            .expect("touch");
+        let edited = stamp(&dir);
+        fs::write(dir.join("b.toml"), "name = \"B\"\n").expect("write");
+        let added = stamp(&dir);
+        let file = stamp(&theme);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(first.len(), 1);
+        assert_ne!(edited, first);
+        assert_eq!(added.len(), 2);
+        assert_eq!(file, edited);
+        assert!(stamp(&theme).is_empty());
+    }
+
+    #[test]
+    fn bad_level_edits_are_reported_and_good_ones_applied() {
+        let dir = temp_dir("level");
+        let path = dir.join("level.txt");
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin);
+        app.init_state::<AppState>();
+        app.init_resource::<ReloadErrors>();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<GameMode>();
+        app.init_resource::<Time<Fixed>>();
+        app.insert_resource(LaunchOptions {
+            level_path: Some(path.clone()),
+            ..LaunchOptions::default()
+        });
+
+        fs::write(&path, "..x\n").expect("write");
+        app.world_mut()
+            .run_system_cached(reload_level)
+            .expect("system runs");
+        let error = app.world().resource::<ReloadErrors>().0.clone();
+
+        fs::write(&path, "#...\n....\n").expect("write");
+        app.world_mut()
+            .run_system_cached(reload_level)
+            .expect("system runs");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(error.len(), 1);
+        assert!(error[0].1.contains("unknown tile"));
+        let world = app.world();
+        assert!(world.resource::<ReloadErrors>().0.is_empty());
+        assert_eq!(
+            world
+                .resource::<LaunchOptions>()
+                .level
+                .as_ref()
+                .map(|l| l.width),
+            Some(4)
+        );
+        assert_eq!(world.resource::<ConfigResource>().0.grid.height, 2);
+    }
+}
@@ -2,13 +2,17 @@ use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
 use beavy_config as config;
 
+use crate::state::ConfigResource;
+use crate::theme::{Role, Theme};
+
 #[derive(Component)]
 pub(crate) struct FpsText;
 
 #[derive(Component)]
 pub(crate) struct GameHudRoot;
 
-pub(crate) fn setup_hud(mut commands: Commands) {
+pub(crate) fn setup_hud(mut commands: Commands, config: Res<ConfigResource>, theme: Res<Theme>) {
+    let config = &config.0;
     commands
         .spawn((
             Node {
@@ -21,15 +25,16 @@ pub(crate) fn setup_hud(mut commands: Commands) {
         .with_children(|parent| {
             parent
                 .spawn((
-                    Text::new(config::text::FPS_LABEL),
+                    Text::new(config.text.fps_label.as_str()),
                     TextFont {
-                        font_size: config::ui::HUD_FONT_SIZE,
+                        font_size: config.ui.hud_font_size,
                         ..default()
                     },
+                    theme.text(Role::Text),
                     Node {
                         position_type: PositionType::Absolute,
-                        top: px(config::ui::FPS_TOP),
-                        left: px(config::ui::FPS_LEFT),
+                        top: px(config.ui.fps_top),
+                        left: px(config.ui.fps_left),
                         ..default()
                     },
                     GameHudRoot,
@@ -37,10 +42,10 @@ pub(crate) fn setup_hud(mut commands: Commands) {
                 .with_child((
                     TextSpan::default(),
                     TextFont {
-                        font_size: config::ui::HUD_FONT_SIZE,
+                        font_size: config.ui.hud_font_size,
                         ..default()
                     },
-                    TextColor(color(config::colors::WHITE)),
+                    theme.text(Role::Text),
                     FpsText,
                     GameHudRoot,
                 ));
@@ -70,18 +75,18 @@ pub(crate) fn cleanup_hud(
     }
 }
 
-fn color(rgb: (f32, f32, f32)) -> Color {
-    Color::srgb(rgb.0, rgb.1, rgb.2)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use bevy::prelude::{App, Entity, With};
 
+    use crate::state::ConfigResource;
+
     #[test]
     fn setup_hud_spawns_entities() {
         let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
         app.add_systems(Startup, setup_hud);
         app.update();
 
@@ -96,6 +101,8 @@ mod tests {
     #[test]
     fn cleanup_hud_despawns_roots() {
         let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
         app.add_systems(Startup, setup_hud);
         app.add_systems(Update, cleanup_hud);
         app.update();
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use beavy_config as config;
+
+use crate::state::GameMode;
+
+/// Overrides given on the command line. They apply to this run only and are
+/// never written back to the config file.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub grid_size: Option<(i32, i32)>,
+    pub tick_seconds: Option<f32>,
+    pub window_size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    pub seed: Option<u64>,
+    pub mode: Option<GameMode>,
+    pub level: Option<game::Level>,
+    /// Where `level` was read from, so it can be reloaded when it changes.
+    pub level_path: Option<PathBuf>,
+    pub replay: Option<game::Replay>,
+}
+
+impl LaunchOptions {
+    /// Loads the user's config file and applies these overrides on top.
+    /// A file that cannot be read or is invalid is an error, not a reason to
+    /// fall back on the defaults.
+    pub fn load_config(&self) -> Result<config::Config, config::ConfigError> {
+        self.overriding(config::load()?)
+    }
+
+    /// `config` with these overrides applied, checked again since they may
+    /// leave it invalid.
+    pub(crate) fn overriding(
+        &self,
+        mut config: config::Config,
+    ) -> Result<config::Config, config::ConfigError> {
+        self.apply(&mut config);
+
+        let errors = config.validate();
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(config::ConfigError::Invalid {
+                path: PathBuf::from("command line"),
+                errors,
+            })
+        }
+    }
+
+    pub fn apply(&self, config: &mut config::Config) {
+        if let Some((width, height)) = self.board_size() {
+            config.grid.width = width;
+            config.grid.height = height;
+        }
+        if let Some(seconds) = self.tick_seconds {
+            config.timing.tick_seconds = seconds;
+        }
+        if let Some((width, height)) = self.window_size {
+            config.window.width = width;
+            config.window.height = height;
+        }
+        if self.fullscreen {
+            config.display.window_mode = config::WindowMode::Fullscreen;
+        }
+    }
+
+    /// Whether to skip the main menu and start playing straight away.
+    pub fn starts_game(&self) -> bool {
+        self.mode.is_some() || self.level.is_some() || self.replay.is_some()
+    }
+
+    /// The first state of a new game: the replay's starting position when
+    /// playing one back, otherwise a fresh game on the level or configured grid.
+    pub fn new_game(&self, config: &config::Config, mode: GameMode) -> game::GameState {
+        if let Some(replay) = &self.replay {
+            return game::new_seeded_game(
+                replay.grid_width,
+                replay.grid_height,
+                replay.seed,
+                replay.rules.clone(),
+            );
+        }
+        let mut rules = mode.rules(config);
+        if let Some(level) = &self.level {
+            rules = level.rules(rules);
+        }
+        game::new_seeded_game(
+            config.grid.width,
+            config.grid.height,
+            self.seed.unwrap_or_else(rand::random),
+            rules,
+        )
+    }
+
+    fn board_size(&self) -> Option<(i32, i32)> {
+        if let Some(replay) = &self.replay {
+            Some((replay.grid_width, replay.grid_height))
+        } else if let Some(level) = &self.level {
+            Some((level.width, level.height))
+        } else {
+            self.grid_size
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_size_overrides_grid_flags() {
+        let options = LaunchOptions {
+            grid_size: Some((30, 20)),
+            level: Some(game::Level::parse("#....\n.....\n....#\n").expect("valid level")),
+            seed: Some(7),
+            ..LaunchOptions::default()
+        };
+        let mut config = config::Config::default();
+        options.apply(&mut config);
+
+        let state = options.new_game(&config, GameMode::Normal);
+
+        assert_eq!((config.grid.width, config.grid.height), (5, 3));
+        assert_eq!((state.grid_width, state.grid_height), (5, 3));
+        assert_eq!(state.rules.obstacles.len(), 2);
+        assert_eq!(state.seed, 7);
+    }
+}
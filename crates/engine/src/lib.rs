@@ -1,8 +1,31 @@
 mod app;
+#[cfg(feature = "audio")]
+mod audio;
+mod bindings;
+mod camera;
+mod controls;
+mod effects;
+mod focus;
+mod gamepads;
 mod gameplay;
 mod gameover;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
 mod hud;
+mod launch;
+mod locale;
 mod menu;
+mod minimap;
+mod modal;
+mod pause;
+mod rewind;
+mod save;
+mod settings;
+mod skin;
 mod state;
+mod theme;
+mod widgets;
 
-pub use app::run;
+pub use app::{run, run_with};
+pub use launch::LaunchOptions;
+pub use state::GameMode;
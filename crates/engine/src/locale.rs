@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use beavy_config as config;
+
+/// Every language that can be selected, English first.
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct Locales(pub(crate) Vec<config::locales::Locale>);
+
+impl Default for Locales {
+    fn default() -> Self {
+        Self(config::locales::builtin())
+    }
+}
+
+impl Locales {
+    /// Loads the locale files. English takes its text from the config file's
+    /// `[text]` section so individual strings can still be overridden.
+    pub(crate) fn load(config: &config::Config) -> (Self, Vec<config::ConfigError>) {
+        let (locales, errors) = config::locales::load_all();
+        let mut locales = Self(locales);
+        locales.set_english(&config.text);
+        (locales, errors)
+    }
+
+    pub(crate) fn set_english(&mut self, text: &config::TextConfig) {
+        if let Some(english) = self
+            .0
+            .iter_mut()
+            .find(|locale| locale.id == config::locales::ENGLISH)
+        {
+            english.text = text.clone();
+        }
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<&config::locales::Locale> {
+        self.0.iter().find(|locale| locale.id == id)
+    }
+
+    /// Text for the language `id`, or English when there is no such locale.
+    pub(crate) fn text(&self, id: &str) -> config::TextConfig {
+        self.get(id)
+            .or_else(|| self.get(config::locales::ENGLISH))
+            .map(|locale| locale.text.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_keeps_config_overrides_and_is_the_fallback() {
+        let mut config = config::Config::default();
+        config.text.menu_start = "Go!".to_string();
+        let mut locales = Locales::default();
+        locales.set_english(&config.text);
+
+        assert_eq!(locales.text("missing").menu_start, "Go!");
+        assert_ne!(locales.text("de").menu_start, "Go!");
+    }
+}
@@ -1,139 +1,108 @@
 use bevy::prelude::*;
-use beavy_config as config;
 
-use crate::state::AppState;
+use crate::modal::{open_modal, Confirm};
+use crate::save::{self, PendingLoad};
+use crate::state::{AppState, ConfigResource, GameMode};
+use crate::theme::{Role, Theme};
+use crate::widgets::{spawn_screen, Pressed};
 
 #[derive(Component)]
 pub(crate) struct MenuRoot;
 
 #[derive(Component, Copy, Clone)]
 pub(crate) enum MenuButtonAction {
+    Continue,
     Start,
+    Practice,
+    Hard,
+    Settings,
     Exit,
 }
 
-pub(crate) fn setup_menu(mut commands: Commands) {
-    commands
-        .spawn((
-            Node {
-                width: percent(config::ui::ROOT_PERCENT),
-                height: percent(config::ui::ROOT_PERCENT),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                row_gap: px(config::ui::PANEL_GAP),
-                ..default()
-            },
-            BackgroundColor(color(config::colors::MENU_BG)),
-            MenuRoot,
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                Text::new(config::text::MENU_TITLE),
-                TextFont {
-                    font_size: config::ui::TITLE_FONT_SIZE,
-                    ..default()
-                },
-                TextColor(color(config::colors::WHITE)),
-                MenuRoot,
-            ));
-
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: px(config::ui::BUTTON_WIDTH),
-                        height: px(config::ui::BUTTON_HEIGHT),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        border: UiRect::all(px(config::ui::BUTTON_BORDER)),
-                        ..default()
-                    },
-                    BorderColor::all(color(config::colors::MENU_BUTTON_BORDER)),
-                    BackgroundColor(color(config::colors::MENU_BUTTON_BG)),
-                    MenuButtonAction::Start,
-                    MenuRoot,
-                ))
-                .with_children(|button| {
-                    button.spawn((
-                        Text::new(config::text::MENU_START),
-                        TextFont {
-                            font_size: config::ui::BUTTON_FONT_SIZE,
-                            ..default()
-                        },
-                        TextColor(color(config::colors::WHITE)),
-                        MenuRoot,
-                    ));
-                });
-
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: px(config::ui::BUTTON_WIDTH),
-                        height: px(config::ui::BUTTON_HEIGHT),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        border: UiRect::all(px(config::ui::BUTTON_BORDER)),
-                        ..default()
-                    },
-                    BorderColor::all(color(config::colors::MENU_BUTTON_BORDER)),
-                    BackgroundColor(color(config::colors::MENU_BUTTON_BG)),
-                    MenuButtonAction::Exit,
-                    MenuRoot,
-                ))
-                .with_children(|button| {
-                    button.spawn((
-                        Text::new(config::text::MENU_EXIT),
-                        TextFont {
-                            font_size: config::ui::BUTTON_FONT_SIZE,
-                            ..default()
-                        },
-                        TextColor(color(config::colors::WHITE)),
-                        MenuRoot,
-                    ));
-                });
-        });
+pub(crate) fn setup_menu(mut commands: Commands, config: Res<ConfigResource>, theme: Res<Theme>) {
+    let config = &config.0;
+    let text = &config.text;
+    let mut items = Vec::new();
+    if save::has_save() {
+        items.push((text.menu_continue.as_str(), MenuButtonAction::Continue));
+    }
+    items.extend([
+        (text.menu_start.as_str(), MenuButtonAction::Start),
+        (text.menu_practice.as_str(), MenuButtonAction::Practice),
+        (text.menu_hard.as_str(), MenuButtonAction::Hard),
+        (text.menu_settings.as_str(), MenuButtonAction::Settings),
+        (text.menu_exit.as_str(), MenuButtonAction::Exit),
+    ]);
+    spawn_screen(
+        &mut commands,
+        config,
+        &theme,
+        Role::MenuBg,
+        MenuRoot,
+        |ui| {
+            ui.title(&text.menu_title);
+            ui.list(items);
+        },
+    );
 }
 
 pub(crate) fn menu_input(
-    mut interactions: Query<(&Interaction, &MenuButtonAction), Changed<Interaction>>,
+    mut commands: Commands,
+    mut pressed: MessageReader<Pressed<MenuButtonAction>>,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut exit: MessageWriter<AppExit>,
 ) {
-    for (interaction, action) in &mut interactions {
-        if *interaction == Interaction::Pressed {
-            match action {
-                MenuButtonAction::Start => next_state.set(AppState::Playing),
-                MenuButtonAction::Exit => {
-                    exit.write(AppExit::Success);
+    for Pressed(action) in pressed.read() {
+        match action {
+            MenuButtonAction::Continue => {
+                match save::read_save() {
+                    Some(saved) => {
+                        commands.insert_resource(saved.mode);
+                        commands.insert_resource(PendingLoad(saved));
+                    }
+                    None => {
+                        warn!("saved game could not be loaded, starting a new one");
+                        commands.insert_resource(GameMode::Normal);
+                    }
                 }
+                next_state.set(AppState::Playing);
+            }
+            MenuButtonAction::Start => {
+                commands.insert_resource(GameMode::Normal);
+                next_state.set(AppState::Playing);
+            }
+            MenuButtonAction::Practice => {
+                commands.insert_resource(GameMode::Practice);
+                next_state.set(AppState::Playing);
+            }
+            MenuButtonAction::Hard => {
+                commands.insert_resource(GameMode::Hard);
+                next_state.set(AppState::Playing);
+            }
+            MenuButtonAction::Settings => {
+                next_state.set(AppState::Settings);
+            }
+            MenuButtonAction::Exit => {
+                open_modal(&mut commands, &config.0, &theme, Confirm::Exit);
             }
         }
     }
 }
 
-pub(crate) fn cleanup_menu(
-    mut commands: Commands,
-    menu_entities: Query<Entity, (With<MenuRoot>, Without<ChildOf>)>,
-) {
-    for entity in &menu_entities {
-        commands.entity(entity).despawn();
-    }
-}
-
-fn color(rgb: (f32, f32, f32)) -> Color {
-    Color::srgb(rgb.0, rgb.1, rgb.2)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use bevy::prelude::{App, Entity, With};
 
+    use crate::state::ConfigResource;
+    use crate::widgets::despawn_screen;
+
     #[test]
     fn setup_menu_spawns_entities() {
         let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
         app.add_systems(Startup, setup_menu);
         app.update();
 
@@ -146,10 +115,12 @@ mod tests {
     }
 
     #[test]
-    fn cleanup_menu_despawns_roots() {
+    fn despawning_the_menu_removes_its_root() {
         let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
         app.add_systems(Startup, setup_menu);
-        app.add_systems(Update, cleanup_menu);
+        app.add_systems(Update, despawn_screen::<MenuRoot>);
         app.update();
 
         let count = {
@@ -0,0 +1,146 @@
+//! A small map of the whole board in the corner of the screen, shown while
+//! the camera follows the snake and only part of the board is in view. It
+//! is an image with one pixel per cell, drawn again whenever the game
+//! changes.
+
+use bevy::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use beavy_config as config;
+
+use crate::hud::GameHudRoot;
+use crate::state::{ConfigResource, GameResource};
+use crate::theme::{Role, Theme};
+
+#[derive(Component)]
+pub(crate) struct Minimap;
+
+pub(crate) fn setup_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: px(config::ui::PANEL_GAP),
+            bottom: px(config::ui::PANEL_GAP),
+            ..default()
+        },
+        ImageNode::new(images.add(blank_map(1, 1))),
+        Visibility::Hidden,
+        Minimap,
+        GameHudRoot,
+    ));
+}
+
+/// Draws the board onto the minimap, resizing it to the board's shape, and
+/// shows it only while the camera follows the snake.
+pub(crate) fn draw_minimap(
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    state: Res<GameResource>,
+    mut images: ResMut<Assets<Image>>,
+    mut minimaps: Query<(&ImageNode, &mut Node, &mut Visibility), With<Minimap>>,
+) {
+    let changed = config.is_changed() || theme.is_changed() || state.is_changed();
+    for (image_node, mut node, mut visibility) in &mut minimaps {
+        let shown = config.0.display.follow_camera;
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if !shown || !(changed || node.is_added()) {
+            continue;
+        }
+        let Some(image) = images.get_mut(&image_node.image) else {
+            continue;
+        };
+        let state = &state.0;
+        let size = UVec2::new(state.grid_width as u32, state.grid_height as u32);
+        if image.size() != size {
+            *image = blank_map(size.x, size.y);
+            let scale = config::camera::MINIMAP_PIXELS / size.max_element() as f32;
+            node.width = px(size.x as f32 * scale);
+            node.height = px(size.y as f32 * scale);
+        }
+        draw_cells(image, &theme, state);
+    }
+}
+
+/// An image `width` by `height` pixels, kept sharp when scaled up.
+fn blank_map(width: u32, height: u32) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Colours each cell's pixel: the board's background, walls, food and the
+/// snake, with row 0 of the image at the top of the board.
+fn draw_cells(image: &mut Image, theme: &Theme, state: &game::GameState) {
+    let pixel = |role: Role, alpha: f32| {
+        let color = theme.color(role).with_alpha(alpha);
+        color.to_srgba().to_u8_array()
+    };
+    let alpha = config::camera::MINIMAP_ALPHA;
+    let background = pixel(Role::Background, alpha);
+    let wall = pixel(Role::Border, 1.0);
+    let food = pixel(Role::Food, 1.0);
+    let snake = pixel(Role::Snake, 1.0);
+    let width = state.grid_width;
+    let Some(data) = image.data.as_mut() else {
+        return;
+    };
+    let mut paint = |cell: game::GridPos, color: [u8; 4]| {
+        let row = state.grid_height - 1 - cell.y;
+        let offset = ((row * width + cell.x) * 4) as usize;
+        if let Some(pixel) = data.get_mut(offset..offset + 4) {
+            pixel.copy_from_slice(&color);
+        }
+    };
+    for y in 0..state.grid_height {
+        for x in 0..width {
+            paint(game::GridPos { x, y }, background);
+        }
+    }
+    for obstacle in &state.rules.obstacles {
+        paint(*obstacle, wall);
+    }
+    paint(state.food, food);
+    for segment in &state.snake {
+        paint(*segment, snake);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_map_shows_the_snake_and_food_with_the_top_row_first() {
+        let theme = Theme::default();
+        let mut state = game::new_game(4, 3);
+        state.snake = vec![game::GridPos { x: 0, y: 2 }];
+        state.food = game::GridPos { x: 3, y: 0 };
+        let mut image = blank_map(4, 3);
+        draw_cells(&mut image, &theme, &state);
+
+        let data = image.data.as_ref().expect("pixel data");
+        let at = |x: usize, y: usize| &data[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+        let color = |role| theme.color(role).to_srgba().to_u8_array();
+        assert_eq!(at(0, 0), color(Role::Snake));
+        assert_eq!(at(3, 2), color(Role::Food));
+        assert_eq!(
+            at(1, 1)[3],
+            (config::camera::MINIMAP_ALPHA * 255.0).round() as u8
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use beavy_config as config;
+
+use crate::focus::BackButton;
+use crate::theme::{Role, Theme};
+use crate::widgets::{spawn_overlay, Pressed};
+
+/// What a confirmation dialog asks the player to agree to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Confirm {
+    Exit,
+    Restart,
+    QuitToMenu,
+}
+
+impl Confirm {
+    fn prompt(self, text: &config::TextConfig) -> &str {
+        match self {
+            Self::Exit => &text.confirm_exit,
+            Self::Restart => &text.confirm_restart,
+            Self::QuitToMenu => &text.confirm_quit,
+        }
+    }
+}
+
+/// Written when the player accepts a dialog; the screen that opened it acts
+/// on the message.
+#[derive(Message, Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct Confirmed(pub(crate) Confirm);
+
+#[derive(Component)]
+pub(crate) struct ModalRoot;
+
+#[derive(Component, Copy, Clone)]
+pub(crate) enum ModalButton {
+    Yes(Confirm),
+    No,
+}
+
+/// Opens a yes/no dialog over the current screen. The full-screen backdrop
+/// keeps clicks from reaching the buttons underneath until it is closed.
+pub(crate) fn open_modal(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    confirm: Confirm,
+) {
+    let text = &config.text;
+    let root = (
+        FocusPolicy::Block,
+        GlobalZIndex(config::ui::MODAL_Z_INDEX),
+        ModalRoot,
+    );
+    spawn_overlay(commands, config, theme, root, |ui| {
+        ui.text(
+            confirm.prompt(text),
+            config.ui.subtitle_font_size,
+            Role::Text,
+        );
+        ui.row(|ui| {
+            ui.button(&text.confirm_yes, ModalButton::Yes(confirm));
+            ui.button(&text.confirm_no, ModalButton::No)
+                .insert(BackButton);
+        });
+    });
+}
+
+/// Closes the dialog once either button is pressed.
+pub(crate) fn modal_input(
+    mut commands: Commands,
+    mut pressed: MessageReader<Pressed<ModalButton>>,
+    roots: Query<Entity, (With<ModalRoot>, Without<ChildOf>)>,
+    mut confirmed: MessageWriter<Confirmed>,
+) {
+    let mut answered = false;
+    for Pressed(button) in pressed.read() {
+        if let ModalButton::Yes(confirm) = button {
+            confirmed.write(Confirmed(*confirm));
+        }
+        answered = true;
+    }
+    if answered {
+        for entity in &roots {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Quits once the player has confirmed [`Confirm::Exit`], from any screen.
+pub(crate) fn exit_on_confirm(
+    mut confirmed: MessageReader<Confirmed>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if confirmed.read().any(|message| message.0 == Confirm::Exit) {
+        exit.write(AppExit::Success);
+    }
+}
+
+pub(crate) fn no_modal(modals: Query<(), With<ModalRoot>>) -> bool {
+    modals.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state::ConfigResource;
+    use crate::widgets::WidgetApp;
+
+    fn modal_app(confirm: Confirm) -> App {
+        let mut app = App::new();
+        app.add_message::<Confirmed>();
+        app.add_actions::<ModalButton>();
+        app.add_systems(Update, modal_input);
+        let config = ConfigResource::default();
+        let mut commands = app.world_mut().commands();
+        open_modal(&mut commands, &config.0, &Theme::default(), confirm);
+        app.world_mut().flush();
+        app
+    }
+
+    fn press(app: &mut App, wanted: fn(&ModalButton) -> bool) {
+        let world = app.world_mut();
+        let button = world
+            .query::<(Entity, &ModalButton)>()
+            .iter(world)
+            .find(|(_, button)| wanted(button))
+            .map(|(entity, _)| entity)
+            .expect("button exists");
+        world.entity_mut(button).insert(Interaction::Pressed);
+        app.update();
+    }
+
+    fn open_count(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world
+            .query_filtered::<Entity, With<ModalRoot>>()
+            .iter(world)
+            .count()
+    }
+
+    fn confirmed(app: &App) -> Vec<Confirmed> {
+        let messages = app.world().resource::<Messages<Confirmed>>();
+        messages.get_cursor().read(messages).copied().collect()
+    }
+
+    #[test]
+    fn yes_confirms_and_closes() {
+        let mut app = modal_app(Confirm::Restart);
+        assert!(open_count(&mut app) > 0);
+
+        press(&mut app, |button| matches!(button, ModalButton::Yes(_)));
+
+        assert_eq!(confirmed(&app), [Confirmed(Confirm::Restart)]);
+        assert_eq!(open_count(&mut app), 0);
+    }
+
+    #[test]
+    fn no_closes_without_confirming() {
+        let mut app = modal_app(Confirm::Exit);
+        press(&mut app, |button| matches!(button, ModalButton::No));
+
+        assert!(confirmed(&app).is_empty());
+        assert_eq!(open_count(&mut app), 0);
+    }
+}
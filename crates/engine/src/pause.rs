@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::window::WindowFocused;
+
+use crate::bindings::{Action, KeyBindings};
+use crate::focus::BackButton;
+use crate::gamepads::GamepadPlayers;
+use crate::gameplay::{restart, InputQueue, ReplayPlayback};
+use crate::launch::LaunchOptions;
+use crate::modal::{open_modal, Confirm, Confirmed};
+use crate::rewind::Rewind;
+use crate::save::{self, PendingLoad, SavedGame};
+use crate::state::{AppState, ConfigResource, GameMode, GameResource, PauseState};
+use crate::theme::Theme;
+use crate::widgets::{spawn_overlay, Pressed};
+
+#[derive(Component)]
+pub(crate) struct PauseRoot;
+
+#[derive(Component, Copy, Clone)]
+pub(crate) enum PauseAction {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+/// Set when the game was put aside for the settings screen, so it comes back
+/// paused rather than running.
+#[derive(Resource)]
+pub(crate) struct ResumePaused;
+
+pub(crate) fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<PauseState>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    let start = gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::Start));
+    if bindings.just_pressed(&keys, Action::Pause) || start {
+        next_state.set(match state.get() {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    }
+}
+
+pub(crate) fn pause_on_focus_lost(
+    mut focus: MessageReader<WindowFocused>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if focus.read().any(|event| !event.focused) {
+        next_state.set(PauseState::Paused);
+    }
+}
+
+/// Pauses when the pad steering the snake is unplugged, until it or another
+/// pad is connected to carry on with.
+pub(crate) fn pause_on_gamepad_lost(
+    mut connections: MessageReader<GamepadConnectionEvent>,
+    players: Res<GamepadPlayers>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if connections.read().any(|event| {
+        event.connection == GamepadConnection::Disconnected && players.is_player_one(event.gamepad)
+    }) {
+        next_state.set(PauseState::Paused);
+    }
+}
+
+pub(crate) fn resume_paused(
+    mut commands: Commands,
+    resume: Option<Res<ResumePaused>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if resume.is_some() {
+        commands.remove_resource::<ResumePaused>();
+        next_state.set(PauseState::Paused);
+    }
+}
+
+pub(crate) fn setup_pause(mut commands: Commands, config: Res<ConfigResource>, theme: Res<Theme>) {
+    let config = &config.0;
+    let text = &config.text;
+    spawn_overlay(&mut commands, config, &theme, PauseRoot, |ui| {
+        ui.title(&text.pause_title);
+        ui.button(&text.pause_resume, PauseAction::Resume)
+            .insert(BackButton);
+        ui.list([
+            (text.pause_restart.as_str(), PauseAction::Restart),
+            (text.pause_settings.as_str(), PauseAction::Settings),
+            (text.pause_quit.as_str(), PauseAction::Quit),
+        ]);
+    });
+}
+
+/// Handles the pause menu. Restarting and quitting give up the run in
+/// progress, so they ask for confirmation first.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pause_input(
+    mut commands: Commands,
+    mut pressed: MessageReader<Pressed<PauseAction>>,
+    mut confirmed: MessageReader<Confirmed>,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    mode: Res<GameMode>,
+    launch: Res<LaunchOptions>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut game: (ResMut<GameResource>, ResMut<Rewind>, ResMut<InputQueue>),
+    mut fixed: ResMut<Time<Fixed>>,
+    mut next_state: (ResMut<NextState<PauseState>>, ResMut<NextState<AppState>>),
+) {
+    let (state, rewind, queue) = &mut game;
+    for Pressed(action) in pressed.read() {
+        match action {
+            PauseAction::Resume => next_state.0.set(PauseState::Running),
+            PauseAction::Restart => open_modal(&mut commands, &config.0, &theme, Confirm::Restart),
+            PauseAction::Settings => {
+                // Leaving `Playing` despawns the board, so carry the game over
+                // to be set up again when the settings screen closes. The
+                // autosave only runs while playing, so save now in case the
+                // window is closed from the settings screen.
+                if playback.is_none() {
+                    save::autosave(&state.0, *mode, rewind);
+                }
+                let saved = SavedGame::new(&state.0, *mode, rewind);
+                commands.insert_resource(PendingLoad(saved));
+                commands.insert_resource(ResumePaused);
+                next_state.1.set(AppState::Settings);
+            }
+            PauseAction::Quit => open_modal(&mut commands, &config.0, &theme, Confirm::QuitToMenu),
+        }
+    }
+    for Confirmed(confirm) in confirmed.read() {
+        match confirm {
+            Confirm::Restart => {
+                restart(&config.0, *mode, &launch, state, rewind, queue, &mut fixed);
+                next_state.0.set(PauseState::Running);
+            }
+            Confirm::QuitToMenu => {
+                if playback.is_none() {
+                    save::autosave(&state.0, *mode, rewind);
+                }
+                next_state.1.set(AppState::Menu);
+            }
+            Confirm::Exit => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    use crate::gamepads::assign_gamepads;
+    use crate::widgets::despawn_screen;
+
+    fn paused_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin);
+        app.insert_resource(ButtonInput::<KeyCode>::default());
+        app.init_resource::<KeyBindings>();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
+        app.insert_state(AppState::Playing);
+        app.add_sub_state::<PauseState>();
+        app.add_systems(Update, toggle_pause.run_if(in_state(AppState::Playing)));
+        app.add_systems(OnEnter(PauseState::Paused), setup_pause);
+        app.add_systems(OnExit(PauseState::Paused), despawn_screen::<PauseRoot>);
+        app.update();
+        app
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+        app.update();
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.release(key);
+        keys.clear();
+        app.update();
+    }
+
+    fn overlay_count(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world
+            .query_filtered::<Entity, With<PauseRoot>>()
+            .iter(world)
+            .count()
+    }
+
+    #[test]
+    fn pause_key_toggles_the_overlay() {
+        let mut app = paused_app();
+        assert_eq!(overlay_count(&mut app), 0);
+
+        press(&mut app, KeyCode::Escape);
+        assert_eq!(
+            *app.world().resource::<State<PauseState>>().get(),
+            PauseState::Paused
+        );
+        assert!(overlay_count(&mut app) > 0);
+
+        press(&mut app, KeyCode::KeyP);
+        assert_eq!(
+            *app.world().resource::<State<PauseState>>().get(),
+            PauseState::Running
+        );
+        assert_eq!(overlay_count(&mut app), 0);
+    }
+
+    #[test]
+    fn leaving_playing_removes_the_overlay() {
+        let mut app = paused_app();
+        press(&mut app, KeyCode::Escape);
+
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::Menu);
+        app.update();
+
+        assert!(app.world().get_resource::<State<PauseState>>().is_none());
+        assert_eq!(overlay_count(&mut app), 0);
+    }
+
+    #[test]
+    fn unplugging_player_ones_pad_pauses() {
+        let mut app = paused_app();
+        app.init_resource::<GamepadPlayers>();
+        app.add_message::<GamepadConnectionEvent>();
+        app.add_systems(
+            Update,
+            (assign_gamepads, pause_on_gamepad_lost)
+                .chain()
+                .run_if(in_state(PauseState::Running)),
+        );
+        let [pad, other] = [(); 2].map(|_| app.world_mut().spawn_empty().id());
+        for gamepad in [pad, other] {
+            app.world_mut().write_message(GamepadConnectionEvent::new(
+                gamepad,
+                GamepadConnection::Connected {
+                    name: "pad".to_string(),
+                    vendor_id: None,
+                    product_id: None,
+                },
+            ));
+        }
+        app.update();
+
+        let state = |app: &App| app.world().resource::<State<PauseState>>().get().clone();
+        app.world_mut()
+            .write_message(GamepadConnectionEvent::new(other, GamepadConnection::Disconnected));
+        app.update();
+        assert_eq!(state(&app), PauseState::Running);
+
+        app.world_mut()
+            .write_message(GamepadConnectionEvent::new(pad, GamepadConnection::Disconnected));
+        app.update();
+        app.update();
+        assert_eq!(state(&app), PauseState::Paused);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use beavy_config as config;
+
+use crate::bindings::{Action, KeyBindings};
+use crate::gameplay::InputQueue;
+use crate::state::{GameMode, GameResource};
+
+/// Snapshots of the last ticks, newest at the back, used to step backwards
+/// while the rewind key is held.
+#[derive(Resource)]
+pub(crate) struct Rewind {
+    history: VecDeque<game::GameState>,
+    capacity: usize,
+    timer: Timer,
+    pub(crate) active: bool,
+    pub(crate) assisted: bool,
+}
+
+impl Rewind {
+    pub(crate) fn new(mode: GameMode, timing: &config::TimingConfig) -> Self {
+        let capacity = match mode {
+            GameMode::Normal | GameMode::Hard => timing.rewind_history_ticks,
+            GameMode::Practice => config::timing::PRACTICE_REWIND_HISTORY_TICKS,
+        };
+        Self {
+            history: VecDeque::new(),
+            capacity,
+            timer: Timer::from_seconds(timing.rewind_tick_seconds, TimerMode::Repeating),
+            active: false,
+            assisted: false,
+        }
+    }
+
+    pub(crate) fn record(&mut self, state: &game::GameState) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.history.len() >= self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(state.clone());
+    }
+
+    fn pop(&mut self) -> Option<game::GameState> {
+        self.history.pop_back()
+    }
+}
+
+pub(crate) fn rewind_game(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mode: Res<GameMode>,
+    mut rewind: ResMut<Rewind>,
+    mut state: ResMut<GameResource>,
+    mut queue: ResMut<InputQueue>,
+) {
+    rewind.active = bindings.pressed(&keys, Action::Rewind);
+    if !rewind.active {
+        rewind.timer.reset();
+        return;
+    }
+
+    let due = bindings.just_pressed(&keys, Action::Rewind)
+        || rewind.timer.tick(time.delta()).just_finished();
+    if !due {
+        return;
+    }
+    if let Some(previous) = rewind.pop() {
+        state.0 = previous;
+        // Turns queued for the abandoned future would steer the restored game.
+        queue.clear();
+        if *mode != GameMode::Practice {
+            rewind.assisted = true;
+        }
+    }
+}
+
+pub(crate) fn not_rewinding(rewind: Option<Res<Rewind>>) -> bool {
+    !rewind.is_some_and(|rewind| rewind.active)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_history_drops_oldest_ticks() {
+        let mut rewind = Rewind::new(GameMode::Normal, &config::TimingConfig::default());
+        let mut state = game::new_game(10, 10);
+        for _ in 0..config::timing::REWIND_HISTORY_TICKS + 5 {
+            rewind.record(&state);
+            state.tick += 1;
+        }
+
+        assert_eq!(rewind.history.len(), config::timing::REWIND_HISTORY_TICKS);
+        assert_eq!(rewind.history.front().map(|state| state.tick), Some(5));
+        assert_eq!(rewind.pop().map(|state| state.tick), Some(state.tick - 1));
+    }
+
+    #[test]
+    fn practice_history_is_longer_but_still_capped() {
+        let mut rewind = Rewind::new(GameMode::Practice, &config::TimingConfig::default());
+        let state = game::new_game(10, 10);
+        for _ in 0..config::timing::PRACTICE_REWIND_HISTORY_TICKS + 5 {
+            rewind.record(&state);
+        }
+
+        assert_eq!(
+            rewind.history.len(),
+            config::timing::PRACTICE_REWIND_HISTORY_TICKS
+        );
+    }
+}
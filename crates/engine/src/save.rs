@@ -0,0 +1,161 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::window::{WindowCloseRequested, WindowFocused};
+use beavy_config as config;
+use serde::{Deserialize, Serialize};
+
+use crate::rewind::Rewind;
+use crate::state::{GameMode, GameResource};
+
+const SAVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SavedGame {
+    version: u32,
+    pub(crate) state: game::GameState,
+    #[serde(default)]
+    pub(crate) mode: GameMode,
+    #[serde(default)]
+    pub(crate) assisted: bool,
+}
+
+/// A save picked from the menu, consumed by `setup_gameplay` on the next run.
+#[derive(Resource)]
+pub(crate) struct PendingLoad(pub(crate) SavedGame);
+
+impl SavedGame {
+    pub(crate) fn new(state: &game::GameState, mode: GameMode, rewind: &Rewind) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            state: state.clone(),
+            mode,
+            assisted: rewind.assisted,
+        }
+    }
+}
+
+pub(crate) fn save_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| {
+        dir.join(config::storage::DATA_DIR)
+            .join(config::storage::SAVE_FILE)
+    })
+}
+
+pub(crate) fn has_save() -> bool {
+    save_path().is_some_and(|path| path.is_file())
+}
+
+pub(crate) fn read_save() -> Option<SavedGame> {
+    parse_save(&fs::read_to_string(save_path()?).ok()?)
+}
+
+/// A save that can be resumed: from this version, still alive, and a state
+/// the game could have reached. Anything else is ignored.
+fn parse_save(text: &str) -> Option<SavedGame> {
+    let saved: SavedGame = serde_json::from_str(text).ok()?;
+    let usable = saved.version == SAVE_VERSION && saved.state.alive;
+    (usable && saved.state.validate().is_ok()).then_some(saved)
+}
+
+pub(crate) fn write_save(
+    state: &game::GameState,
+    mode: GameMode,
+    rewind: &Rewind,
+) -> io::Result<()> {
+    let path = save_path().ok_or_else(|| io::Error::other("no user data directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let saved = SavedGame::new(state, mode, rewind);
+    let text = serde_json::to_string(&saved).map_err(io::Error::other)?;
+    fs::write(path, text)
+}
+
+pub(crate) fn delete_save() {
+    if let Some(path) = save_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub(crate) fn autosave_on_focus_lost(
+    mut focus: MessageReader<WindowFocused>,
+    state: Res<GameResource>,
+    mode: Res<GameMode>,
+    rewind: Res<Rewind>,
+) {
+    if focus.read().any(|event| !event.focused) {
+        autosave(&state.0, *mode, &rewind);
+    }
+}
+
+pub(crate) fn autosave_on_close(
+    mut close: MessageReader<WindowCloseRequested>,
+    state: Res<GameResource>,
+    mode: Res<GameMode>,
+    rewind: Res<Rewind>,
+) {
+    if close.read().next().is_some() {
+        autosave(&state.0, *mode, &rewind);
+    }
+}
+
+pub(crate) fn autosave(state: &game::GameState, mode: GameMode, rewind: &Rewind) {
+    if !state.alive {
+        return;
+    }
+    if let Err(err) = write_save(state, mode, rewind) {
+        warn!("failed to save game: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_game_round_trip_keeps_rng_state() {
+        let mut state = game::new_game(10, 10);
+        let head = state.snake[0];
+        state.food = game::GridPos {
+            x: head.x + 1,
+            y: head.y,
+        };
+        let saved = SavedGame {
+            version: SAVE_VERSION,
+            state: state.clone(),
+            mode: GameMode::Practice,
+            assisted: true,
+        };
+
+        let text = serde_json::to_string(&saved).expect("serializable save");
+        let mut restored: SavedGame = serde_json::from_str(&text).expect("readable save");
+        game::step(&mut state);
+        game::step(&mut restored.state);
+
+        assert_eq!(restored.state.food, state.food);
+        assert_eq!(restored.state.rng, state.rng);
+        assert_eq!(restored.mode, GameMode::Practice);
+        assert!(restored.assisted);
+    }
+
+    #[test]
+    fn corrupt_saves_are_ignored() {
+        let text = |state: &game::GameState| {
+            let saved = SavedGame {
+                version: SAVE_VERSION,
+                state: state.clone(),
+                mode: GameMode::Normal,
+                assisted: false,
+            };
+            serde_json::to_string(&saved).expect("serializable save")
+        };
+        let mut state = game::new_game(10, 10);
+        assert!(parse_save(&text(&state)).is_some());
+
+        state.snake.push(state.snake[0]);
+        assert!(parse_save(&text(&state)).is_none());
+    }
+}
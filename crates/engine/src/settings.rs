@@ -0,0 +1,516 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PrimaryWindow, VideoModeSelection, WindowMode};
+use beavy_config as config;
+
+use crate::focus::BackButton;
+use crate::locale::Locales;
+use crate::save::PendingLoad;
+use crate::state::{AppState, ConfigResource};
+use crate::theme::{Role, Theme, Themes};
+use crate::widgets::{spawn_screen, Pressed, Slider, Toggle, Ui};
+
+#[derive(Component)]
+pub(crate) struct SettingsRoot;
+
+#[derive(Component, Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Setting {
+    Grid,
+    Speed,
+    Wrap,
+    Smooth,
+    ReducedMotion,
+    FollowCamera,
+    Theme,
+    Window,
+    Volume,
+    Music,
+    Mute,
+    Language,
+}
+
+impl Setting {
+    const ALL: [Self; 12] = [
+        Self::Grid,
+        Self::Speed,
+        Self::Wrap,
+        Self::Smooth,
+        Self::ReducedMotion,
+        Self::FollowCamera,
+        Self::Theme,
+        Self::Window,
+        Self::Volume,
+        Self::Music,
+        Self::Mute,
+        Self::Language,
+    ];
+
+    /// Whether the setting is switched on and off rather than picked.
+    fn is_toggle(self) -> bool {
+        matches!(
+            self,
+            Self::Wrap | Self::Smooth | Self::ReducedMotion | Self::FollowCamera | Self::Mute
+        )
+    }
+}
+
+#[derive(Component, Copy, Clone)]
+pub(crate) enum SettingsButtonAction {
+    Previous(Setting),
+    Next(Setting),
+    Controls,
+    Back,
+}
+
+/// The widget showing a setting's current value.
+#[derive(Component)]
+pub(crate) struct SettingValue(Setting);
+
+/// The themes and languages the screen cycles through.
+#[derive(Copy, Clone)]
+struct Choices<'a> {
+    themes: &'a Themes,
+    locales: &'a Locales,
+}
+
+pub(crate) fn setup_settings(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    theme: Res<Theme>,
+    themes: Res<Themes>,
+    locales: Res<Locales>,
+) {
+    let choices = Choices {
+        themes: &themes,
+        locales: &locales,
+    };
+    spawn_settings(&mut commands, &config.0, &theme, choices);
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn settings_input(
+    mut commands: Commands,
+    mut pressed: MessageReader<Pressed<SettingsButtonAction>>,
+    mut config: ResMut<ConfigResource>,
+    mut theme: ResMut<Theme>,
+    themes: Res<Themes>,
+    locales: Res<Locales>,
+    mut values: Query<(
+        &SettingValue,
+        Option<&mut Text>,
+        Option<&mut Toggle>,
+        Option<&mut Slider>,
+    )>,
+    roots: Query<Entity, (With<SettingsRoot>, Without<ChildOf>)>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    paused_game: Option<Res<PendingLoad>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut changed: Local<Vec<Setting>>,
+) {
+    let choices = Choices {
+        themes: &themes,
+        locales: &locales,
+    };
+    for Pressed(action) in pressed.read() {
+        let (setting, step) = match *action {
+            SettingsButtonAction::Previous(setting) => (setting, -1),
+            SettingsButtonAction::Next(setting) => (setting, 1),
+            SettingsButtonAction::Controls => {
+                next_state.set(AppState::Controls);
+                continue;
+            }
+            SettingsButtonAction::Back => {
+                match save_settings(&config.0, &changed) {
+                    Ok(path) => info!("settings saved to {}", path.display()),
+                    Err(err) => warn!("settings could not be saved: {err}"),
+                }
+                changed.clear();
+                // Opened from the pause menu: go back to the game in progress.
+                next_state.set(if paused_game.is_some() {
+                    AppState::Playing
+                } else {
+                    AppState::Menu
+                });
+                continue;
+            }
+        };
+        adjust(&mut config.0, choices, setting, step);
+        if !changed.contains(&setting) {
+            changed.push(setting);
+        }
+
+        match setting {
+            Setting::Window => {
+                if let Ok(mut window) = windows.single_mut() {
+                    window.mode = window_mode(config.0.display.window_mode);
+                }
+            }
+            Setting::Theme => *theme = Theme::select(&config.0, &themes),
+            Setting::Language => {
+                // Every label changes, so rebuild the screen in the new language.
+                config.0.text = locales.text(&config.0.display.language);
+                for entity in &roots {
+                    commands.entity(entity).despawn();
+                }
+                spawn_settings(&mut commands, &config.0, &theme, choices);
+                continue;
+            }
+            _ => {}
+        }
+        for (value, text, toggle, slider) in &mut values {
+            if value.0 != setting {
+                continue;
+            }
+            if let Some(mut text) = text {
+                **text = value_label(&config.0, choices, setting);
+            }
+            if let Some(mut toggle) = toggle {
+                toggle.0 = is_on(&config.0, setting);
+            }
+            if let Some(mut slider) = slider {
+                *slider = setting_slider(&config.0, choices, setting);
+            }
+        }
+    }
+}
+
+pub(crate) fn window_mode(mode: config::WindowMode) -> WindowMode {
+    match mode {
+        config::WindowMode::Windowed => WindowMode::Windowed,
+        config::WindowMode::Borderless => {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        }
+        config::WindowMode::Fullscreen => {
+            WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+        }
+    }
+}
+
+/// Moves `setting` one option forwards or backwards, wrapping at either end.
+/// Grid sizes that would leave the configuration invalid are skipped.
+fn adjust(config: &mut config::Config, choices: Choices, setting: Setting, step: i32) {
+    match setting {
+        Setting::Grid => {
+            let sizes = config::presets::GRID_SIZES;
+            let current = (config.grid.width, config.grid.height);
+            let mut index = sizes.iter().position(|size| *size == current);
+            for _ in 0..sizes.len() {
+                let next = cycle(index, sizes.len(), step);
+                index = Some(next);
+                let mut candidate = config.clone();
+                (candidate.grid.width, candidate.grid.height) = sizes[next];
+                if candidate.validate().is_empty() {
+                    (config.grid.width, config.grid.height) = sizes[next];
+                    return;
+                }
+            }
+        }
+        Setting::Speed => {
+            let speeds = config::presets::TICK_SECONDS;
+            let index = speeds
+                .iter()
+                .position(|seconds| *seconds == config.timing.tick_seconds);
+            config.timing.tick_seconds = speeds[cycle(index, speeds.len(), step)];
+        }
+        Setting::Wrap => config.gameplay.wrap_walls = !config.gameplay.wrap_walls,
+        Setting::Smooth => config.display.smooth_movement = !config.display.smooth_movement,
+        Setting::ReducedMotion => config.display.reduced_motion = !config.display.reduced_motion,
+        Setting::FollowCamera => config.display.follow_camera = !config.display.follow_camera,
+        Setting::Theme => {
+            let themes = &choices.themes.0;
+            let index = themes
+                .iter()
+                .position(|theme| theme.id == config.display.theme);
+            config.display.theme = themes[cycle(index, themes.len(), step)].id.clone();
+        }
+        Setting::Window => {
+            let modes = [
+                config::WindowMode::Windowed,
+                config::WindowMode::Borderless,
+                config::WindowMode::Fullscreen,
+            ];
+            let index = modes
+                .iter()
+                .position(|mode| *mode == config.display.window_mode);
+            config.display.window_mode = modes[cycle(index, modes.len(), step)];
+        }
+        Setting::Volume => config.audio.volume = step_volume(config.audio.volume, step),
+        Setting::Music => config.audio.music_volume = step_volume(config.audio.music_volume, step),
+        Setting::Mute => config.audio.muted = !config.audio.muted,
+        Setting::Language => {
+            let locales = &choices.locales.0;
+            let index = locales
+                .iter()
+                .position(|locale| locale.id == config.display.language);
+            config.display.language = locales[cycle(index, locales.len(), step)].id.clone();
+        }
+    }
+}
+
+/// Writes the `changed` settings into the config file, leaving every other
+/// value as the file has it so command-line overrides are not persisted. A
+/// file broken since the game started is replaced, starting from the
+/// defaults, rather than losing the changes.
+fn save_settings(
+    config: &config::Config,
+    changed: &[Setting],
+) -> Result<PathBuf, config::ConfigError> {
+    let mut saved = match config::load() {
+        Ok(saved) => saved,
+        Err(err @ (config::ConfigError::Parse { .. } | config::ConfigError::Invalid { .. })) => {
+            warn!("replacing the config file, which could not be used: {err}");
+            config::Config::default()
+        }
+        Err(err) => return Err(err),
+    };
+    for setting in changed {
+        match setting {
+            Setting::Grid => {
+                saved.grid.width = config.grid.width;
+                saved.grid.height = config.grid.height;
+            }
+            Setting::Speed => saved.timing.tick_seconds = config.timing.tick_seconds,
+            Setting::Wrap => saved.gameplay.wrap_walls = config.gameplay.wrap_walls,
+            Setting::Smooth => saved.display.smooth_movement = config.display.smooth_movement,
+            Setting::ReducedMotion => {
+                saved.display.reduced_motion = config.display.reduced_motion;
+            }
+            Setting::FollowCamera => saved.display.follow_camera = config.display.follow_camera,
+            Setting::Theme => saved.display.theme = config.display.theme.clone(),
+            Setting::Window => saved.display.window_mode = config.display.window_mode,
+            Setting::Volume => saved.audio.volume = config.audio.volume,
+            Setting::Music => saved.audio.music_volume = config.audio.music_volume,
+            Setting::Mute => saved.audio.muted = config.audio.muted,
+            Setting::Language => saved.display.language = config.display.language.clone(),
+        }
+    }
+    config::save(&saved)
+}
+
+/// A volume moved `step` notches, kept between silent and full.
+fn step_volume(volume: f32, step: i32) -> f32 {
+    let steps = (volume / config::audio::VOLUME_STEP).round() as i32 + step;
+    (steps as f32 * config::audio::VOLUME_STEP).clamp(0.0, 1.0)
+}
+
+/// Index after moving `step` places from `index`; an unknown current value
+/// starts from the first option.
+fn cycle(index: Option<usize>, len: usize, step: i32) -> usize {
+    match index {
+        Some(index) => (index as i32 + step).rem_euclid(len as i32) as usize,
+        None => 0,
+    }
+}
+
+fn setting_label(text: &config::TextConfig, setting: Setting) -> &str {
+    match setting {
+        Setting::Grid => &text.settings_grid,
+        Setting::Speed => &text.settings_speed,
+        Setting::Wrap => &text.settings_wrap,
+        Setting::Smooth => &text.settings_smooth,
+        Setting::ReducedMotion => &text.settings_reduced_motion,
+        Setting::FollowCamera => &text.settings_follow_camera,
+        Setting::Theme => &text.settings_theme,
+        Setting::Window => &text.settings_window,
+        Setting::Volume => &text.settings_volume,
+        Setting::Music => &text.settings_music,
+        Setting::Mute => &text.settings_mute,
+        Setting::Language => &text.settings_language,
+    }
+}
+
+/// The value of an on/off setting.
+fn is_on(config: &config::Config, setting: Setting) -> bool {
+    match setting {
+        Setting::Wrap => config.gameplay.wrap_walls,
+        Setting::Smooth => config.display.smooth_movement,
+        Setting::ReducedMotion => config.display.reduced_motion,
+        Setting::FollowCamera => config.display.follow_camera,
+        Setting::Mute => config.audio.muted,
+        _ => false,
+    }
+}
+
+fn value_label(config: &config::Config, choices: Choices, setting: Setting) -> String {
+    let text = &config.text;
+    match setting {
+        Setting::Grid => text
+            .settings_grid_value
+            .replace("{width}", &config.grid.width.to_string())
+            .replace("{height}", &config.grid.height.to_string()),
+        Setting::Speed => {
+            let ms = (config.timing.tick_seconds * 1000.0).round() as u64;
+            text.settings_speed_value
+                .replace("{ms}", &text.format_number(ms))
+        }
+        Setting::Wrap
+        | Setting::Smooth
+        | Setting::ReducedMotion
+        | Setting::FollowCamera
+        | Setting::Mute => {
+            if is_on(config, setting) {
+                text.on.clone()
+            } else {
+                text.off.clone()
+            }
+        }
+        Setting::Theme => match choices.themes.get(&config.display.theme) {
+            Some(theme) => theme.name.clone(),
+            None => config.display.theme.clone(),
+        },
+        Setting::Window => match config.display.window_mode {
+            config::WindowMode::Windowed => text.window_windowed.clone(),
+            config::WindowMode::Borderless => text.window_borderless.clone(),
+            config::WindowMode::Fullscreen => text.window_fullscreen.clone(),
+        },
+        Setting::Volume | Setting::Music => {
+            let volume = match setting {
+                Setting::Music => config.audio.music_volume,
+                _ => config.audio.volume,
+            };
+            let percent = (volume * 100.0).round() as u64;
+            text.settings_volume_value
+                .replace("{percent}", &text.format_number(percent))
+        }
+        Setting::Language => match choices.locales.get(&config.display.language) {
+            Some(locale) => locale.name.clone(),
+            None => config.display.language.clone(),
+        },
+    }
+}
+
+/// How full the bar of a slider setting is, from its position in the
+/// presets or its value.
+fn setting_slider(config: &config::Config, choices: Choices, setting: Setting) -> Slider {
+    let fraction = match setting {
+        Setting::Speed => {
+            let speeds = config::presets::TICK_SECONDS;
+            let index = speeds
+                .iter()
+                .position(|seconds| *seconds == config.timing.tick_seconds)
+                .unwrap_or(0);
+            index as f32 / (speeds.len() - 1) as f32
+        }
+        Setting::Volume => config.audio.volume,
+        Setting::Music => config.audio.music_volume,
+        _ => 0.0,
+    };
+    Slider {
+        fraction,
+        text: value_label(config, choices, setting),
+    }
+}
+
+fn spawn_settings(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    choices: Choices,
+) {
+    spawn_screen(commands, config, theme, Role::MenuBg, SettingsRoot, |ui| {
+        ui.title(&config.text.settings_title);
+        for setting in Setting::ALL {
+            ui.field(setting_label(&config.text, setting), |ui| {
+                spawn_setting(ui, choices, setting);
+            });
+        }
+        ui.row(|ui| {
+            ui.button(
+                &config.text.settings_controls,
+                SettingsButtonAction::Controls,
+            );
+            ui.button(&config.text.settings_back, SettingsButtonAction::Back)
+                .insert(BackButton);
+        });
+    });
+}
+
+fn spawn_setting(ui: &mut Ui, choices: Choices, setting: Setting) {
+    let config = ui.config;
+    let previous = SettingsButtonAction::Previous(setting);
+    let next = SettingsButtonAction::Next(setting);
+    let mut value = match setting {
+        _ if setting.is_toggle() => ui.toggle(is_on(config, setting), next),
+        Setting::Speed | Setting::Volume | Setting::Music => {
+            ui.slider(setting_slider(config, choices, setting), previous, next)
+        }
+        _ => ui.choice(&value_label(config, choices, setting), previous, next),
+    };
+    value.insert(SettingValue(setting));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjust_cycles_and_wraps() {
+        let mut config = config::Config::default();
+        let themes = Themes::default();
+        let locales = Locales::default();
+        let choices = Choices {
+            themes: &themes,
+            locales: &locales,
+        };
+
+        adjust(&mut config, choices, Setting::Speed, 1);
+        assert_eq!(config.timing.tick_seconds, config::presets::TICK_SECONDS[2]);
+        adjust(&mut config, choices, Setting::Theme, -1);
+        assert_eq!(config.display.theme, "high_contrast");
+        adjust(&mut config, choices, Setting::Volume, 5);
+        assert_eq!(config.audio.volume, 1.0);
+        adjust(&mut config, choices, Setting::Wrap, 1);
+        assert!(config.gameplay.wrap_walls);
+        adjust(&mut config, choices, Setting::Smooth, 1);
+        assert!(!config.display.smooth_movement);
+        adjust(&mut config, choices, Setting::ReducedMotion, 1);
+        assert!(config.display.reduced_motion);
+        adjust(&mut config, choices, Setting::FollowCamera, 1);
+        assert!(config.display.follow_camera);
+        adjust(&mut config, choices, Setting::Music, -1);
+        assert_eq!(config.audio.music_volume, 0.4);
+        adjust(&mut config, choices, Setting::Mute, 1);
+        assert!(config.audio.muted);
+        adjust(&mut config, choices, Setting::Language, 1);
+        assert_eq!(config.display.language, "de");
+    }
+
+    #[test]
+    fn adjust_offers_grids_larger_than_the_window() {
+        let mut config = config::Config::default();
+        config.window.width = 300;
+        let themes = Themes::default();
+        let locales = Locales::default();
+        let choices = Choices {
+            themes: &themes,
+            locales: &locales,
+        };
+
+        let mut seen = Vec::new();
+        for _ in 0..config::presets::GRID_SIZES.len() {
+            adjust(&mut config, choices, Setting::Grid, 1);
+            assert!(config.validate().is_empty());
+            seen.push((config.grid.width, config.grid.height));
+        }
+        seen.sort();
+        let mut sizes = config::presets::GRID_SIZES.to_vec();
+        sizes.sort();
+        assert_eq!(seen, sizes);
+    }
+
+    #[test]
+    fn setup_settings_spawns_a_row_per_setting() {
+        let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.init_resource::<Theme>();
+        app.init_resource::<Themes>();
+        app.init_resource::<Locales>();
+        app.add_systems(Startup, setup_settings);
+        app.update();
+
+        let world = app.world_mut();
+        let mut query = world.query::<&SettingValue>();
+        assert_eq!(query.iter(world).count(), Setting::ALL.len());
+    }
+}
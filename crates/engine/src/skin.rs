@@ -0,0 +1,307 @@
+//! How the snake is drawn.
+//!
+//! Every segment shows one frame of a sprite sheet: a head, a straight piece
+//! of body, a corner or a tail, each drawn facing up and rotated into place.
+//! The built-in sheet is generated at startup as white shapes, so the theme's
+//! snake colour tints it like any other sprite. A skin from the assets folder
+//! is a PNG laid out the same way: four square frames side by side, in the
+//! order of [`SegmentShape`].
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy::asset::{AssetLoadFailedEvent, RenderAssetUsages};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use beavy_config as config;
+
+use crate::state::ConfigResource;
+
+/// The frames of a skin, in sheet order.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum SegmentShape {
+    Head,
+    Body,
+    Corner,
+    Tail,
+}
+
+impl SegmentShape {
+    const ALL: [Self; 4] = [Self::Head, Self::Body, Self::Corner, Self::Tail];
+}
+
+/// The sprite sheet the snake is drawn from.
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct SnakeSkin {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+impl SnakeSkin {
+    /// Shows `shape` on `sprite`.
+    pub(crate) fn apply(&self, sprite: &mut Sprite, shape: SegmentShape) {
+        sprite.image = self.image.clone();
+        sprite.texture_atlas = Some(TextureAtlas {
+            layout: self.layout.clone(),
+            index: shape as usize,
+        });
+    }
+}
+
+/// How far the tail's tip reaches below the middle of its cell.
+const TAIL_TIP: f32 = 0.3;
+/// Samples per pixel along each axis, to smooth the edges of the shapes.
+const SAMPLES: u32 = 4;
+/// Brightness of the eyes; the rest of the sheet is white.
+const EYE_SHADE: u32 = 24;
+
+pub(crate) fn setup_skin(
+    mut commands: Commands,
+    config: Res<ConfigResource>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let config = &config.0;
+    let image = match config.display.skin.as_str() {
+        "" => images.add(draw_shapes(&config.grid)),
+        skin => asset_server.load(format!("{}/{skin}.png", config::storage::SKINS_DIR)),
+    };
+    let layout = layouts.add(sheet_layout(config::grid::SKIN_FRAME_PIXELS));
+    commands.insert_resource(SnakeSkin { image, layout });
+}
+
+/// Fits the sheet layout to a skin once it has loaded, or draws the
+/// built-in shapes in its place if it could not be.
+pub(crate) fn load_skin(
+    mut loaded: MessageReader<AssetEvent<Image>>,
+    mut failed: MessageReader<AssetLoadFailedEvent<Image>>,
+    config: Res<ConfigResource>,
+    skin: Res<SnakeSkin>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    for event in loaded.read() {
+        if let AssetEvent::LoadedWithDependencies { id } = event
+            && *id == skin.image.id()
+            && let Some(image) = images.get(*id)
+            && let Some(layout) = layouts.get_mut(&skin.layout)
+        {
+            *layout = sheet_layout(image.height());
+        }
+    }
+    for event in failed.read() {
+        if event.id != skin.image.id() {
+            continue;
+        }
+        warn!("{}: {}; using the built-in snake", event.path, event.error);
+        if let Err(err) = images.insert(&skin.image, draw_shapes(&config.0.grid)) {
+            warn!("built-in snake could not replace the skin: {err}");
+        }
+    }
+}
+
+/// Square frames `frame` pixels across, side by side.
+fn sheet_layout(frame: u32) -> TextureAtlasLayout {
+    let count = SegmentShape::ALL.len() as u32;
+    TextureAtlasLayout::from_grid(UVec2::splat(frame), count, 1, None, None)
+}
+
+/// The frame drawing segment `index` and the rotation that turns its top
+/// towards the segment ahead, or for the head the way it is moving.
+pub(crate) fn segment_shape(state: &game::GameState, index: usize) -> (SegmentShape, f32) {
+    let snake = &state.snake;
+    if index == 0 {
+        return (SegmentShape::Head, angle(direction_step(state.direction)));
+    }
+    let ahead = step_towards(state, snake[index], snake[index - 1]);
+    let Some(next) = snake.get(index + 1) else {
+        return (SegmentShape::Tail, angle(ahead));
+    };
+    let behind = step_towards(state, snake[index], *next);
+    if ahead == -behind {
+        return (SegmentShape::Body, angle(ahead));
+    }
+    // The corner frame joins the bottom and right edges; find the quarter
+    // turn that brings them onto the two neighbours.
+    let mut edges = (IVec2::NEG_Y, IVec2::X);
+    for turn in 0..4 {
+        if edges == (ahead, behind) || edges == (behind, ahead) {
+            return (SegmentShape::Corner, turn as f32 * FRAC_PI_2);
+        }
+        edges = (edges.0.perp(), edges.1.perp());
+    }
+    (SegmentShape::Body, angle(ahead))
+}
+
+fn direction_step(direction: game::Direction) -> IVec2 {
+    match direction {
+        game::Direction::Up => IVec2::Y,
+        game::Direction::Down => IVec2::NEG_Y,
+        game::Direction::Left => IVec2::NEG_X,
+        game::Direction::Right => IVec2::X,
+    }
+}
+
+/// The step from `from` to its neighbour `to`, counting a move across a
+/// wrapped edge as a single step.
+fn step_towards(state: &game::GameState, from: game::GridPos, to: game::GridPos) -> IVec2 {
+    let wrap = |delta: i32, size: i32| match delta {
+        delta if delta > 1 => delta - size,
+        delta if delta < -1 => delta + size,
+        delta => delta,
+    };
+    IVec2::new(
+        wrap(to.x - from.x, state.grid_width),
+        wrap(to.y - from.y, state.grid_height),
+    )
+    .signum()
+}
+
+/// The rotation that turns up into `step`.
+fn angle(step: IVec2) -> f32 {
+    (-step.x as f32).atan2(step.y as f32)
+}
+
+/// The built-in sheet: white shapes on a transparent background, the body
+/// as wide as `snake_size` is within a cell.
+fn draw_shapes(grid: &config::GridConfig) -> Image {
+    let frame = config::grid::SKIN_FRAME_PIXELS;
+    let count = SegmentShape::ALL.len() as u32;
+    let half = grid.snake_size / grid.cell_size / 2.0;
+    let mut data = vec![0; (frame * count * frame * 4) as usize];
+    for (column, shape) in SegmentShape::ALL.into_iter().enumerate() {
+        for y in 0..frame {
+            for x in 0..frame {
+                let offset = ((y * frame * count + column as u32 * frame + x) * 4) as usize;
+                data[offset..offset + 4].copy_from_slice(&shade(shape, half, x, y, frame));
+            }
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: frame * count,
+            height: frame,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// The colour of pixel (`x`, `y`) of a frame of `shape`.
+fn shade(shape: SegmentShape, half: f32, x: u32, y: u32, frame: u32) -> [u8; 4] {
+    let (mut body, mut eyes) = (0, 0);
+    for sample_y in 0..SAMPLES {
+        for sample_x in 0..SAMPLES {
+            let offset = |pixel: u32, sample: u32| {
+                (pixel as f32 + (sample as f32 + 0.5) / SAMPLES as f32) / frame as f32
+            };
+            // Centred on the cell with y up, one unit across.
+            let point = Vec2::new(offset(x, sample_x) - 0.5, 0.5 - offset(y, sample_y));
+            match covers(shape, half, point) {
+                Some(Fill::Body) => body += 1,
+                Some(Fill::Eye) => eyes += 1,
+                None => {}
+            }
+        }
+    }
+    let covered = body + eyes;
+    if covered == 0 {
+        return [0; 4];
+    }
+    let light = ((body * 255 + eyes * EYE_SHADE) / covered) as u8;
+    let alpha = (covered * 255 / (SAMPLES * SAMPLES)) as u8;
+    [light, light, light, alpha]
+}
+
+#[derive(Debug, PartialEq)]
+enum Fill {
+    Body,
+    Eye,
+}
+
+/// What `point` of a frame facing up lies on, for a body reaching `half` a
+/// cell either side of its centre line.
+fn covers(shape: SegmentShape, half: f32, point: Vec2) -> Option<Fill> {
+    let inside = match shape {
+        SegmentShape::Body => point.x.abs() <= half,
+        // A quarter ring around the corner between the bottom and right edges.
+        SegmentShape::Corner => (point.distance(Vec2::new(0.5, -0.5)) - 0.5).abs() <= half,
+        // Joins the body along the bottom edge and is rounded at the front.
+        SegmentShape::Head => point.x.abs() <= half && (point.y <= 0.0 || point.length() <= half),
+        // Joins the body along the top edge and narrows to a point.
+        SegmentShape::Tail => {
+            let taper = (point.y + TAIL_TIP) / (0.5 + TAIL_TIP);
+            (0.0..=1.0).contains(&taper) && point.x.abs() <= half * taper
+        }
+    };
+    if !inside {
+        return None;
+    }
+    let on_eye =
+        |side: f32| point.distance(Vec2::new(side * half * 0.45, half * 0.35)) <= half * 0.2;
+    if shape == SegmentShape::Head && (on_eye(-1.0) || on_eye(1.0)) {
+        Some(Fill::Eye)
+    } else {
+        Some(Fill::Body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snake(cells: &[(i32, i32)], direction: game::Direction) -> game::GameState {
+        let mut state = game::new_game(10, 10);
+        state.snake = cells.iter().map(|&(x, y)| game::GridPos { x, y }).collect();
+        state.direction = direction;
+        state
+    }
+
+    #[test]
+    fn shapes_follow_the_snake() {
+        // Heading up, then along to the left, turning at (5, 5).
+        let state = snake(&[(5, 6), (5, 5), (6, 5), (7, 5)], game::Direction::Up);
+
+        assert_eq!(segment_shape(&state, 0), (SegmentShape::Head, 0.0));
+        let (corner, turn) = segment_shape(&state, 1);
+        assert_eq!(corner, SegmentShape::Corner);
+        assert!((turn - FRAC_PI_2).abs() < 1e-6);
+        let (body, turn) = segment_shape(&state, 2);
+        assert_eq!(body, SegmentShape::Body);
+        assert!((turn - FRAC_PI_2).abs() < 1e-6);
+        let (tail, turn) = segment_shape(&state, 3);
+        assert_eq!(tail, SegmentShape::Tail);
+        assert!((turn - FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn wrapped_neighbours_count_as_adjacent() {
+        // The head has wrapped from the right edge to the left one.
+        let state = snake(&[(0, 4), (9, 4), (8, 4)], game::Direction::Right);
+
+        let (body, turn) = segment_shape(&state, 1);
+        assert_eq!(body, SegmentShape::Body);
+        assert!((turn + FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn built_in_sheet_draws_each_shape() {
+        let frame = config::grid::SKIN_FRAME_PIXELS;
+        let image = draw_shapes(&config::GridConfig::default());
+        let pixel = |shape: SegmentShape, x: u32, y: u32| {
+            let x = shape as u32 * frame + x;
+            let offset = ((y * image.width() + x) * 4) as usize;
+            let data = image.data.as_ref().expect("pixel data");
+            [data[offset], data[offset + 3]]
+        };
+
+        assert_eq!(image.width(), frame * 4);
+        assert_eq!(pixel(SegmentShape::Body, frame / 2, 0), [255, 255]);
+        assert_eq!(pixel(SegmentShape::Head, frame / 2, 0)[1], 0);
+        assert_eq!(pixel(SegmentShape::Corner, 0, 0)[1], 0);
+        assert_eq!(pixel(SegmentShape::Tail, frame / 2, frame - 1)[1], 0);
+    }
+}
@@ -1,12 +1,54 @@
 use bevy::prelude::*;
+use beavy_config as config;
+use serde::{Deserialize, Serialize};
 
 #[derive(Resource)]
 pub struct GameResource(pub game::GameState);
 
+#[derive(Resource, Default)]
+pub struct ConfigResource(pub config::Config);
+
 #[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
 pub enum AppState {
     #[default]
     Menu,
     Playing,
     GameOver,
+    Settings,
+    Controls,
+}
+
+/// Whether a game in progress is running. Pausing keeps `AppState::Playing`,
+/// so the board stays spawned underneath the pause menu.
+#[derive(SubStates, Debug, Clone, Eq, PartialEq, Hash, Default)]
+#[source(AppState = AppState::Playing)]
+pub(crate) enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+#[derive(Resource, Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Normal,
+    Practice,
+    Hard,
+}
+
+impl GameMode {
+    pub fn rules(self, config: &config::Config) -> game::Rules {
+        let food = match self {
+            Self::Normal => game::FoodStrategy::Uniform,
+            Self::Practice => game::FoodStrategy::Fair {
+                min_distance: config.grid.fair_food_distance,
+            },
+            Self::Hard => game::FoodStrategy::EdgeBiased,
+        };
+        game::Rules {
+            wrap_walls: config.gameplay.wrap_walls,
+            food,
+            ..game::Rules::default()
+        }
+    }
 }
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use beavy_config as config;
+
+/// Every theme that can be selected, built-in ones first.
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct Themes(pub(crate) Vec<config::themes::Theme>);
+
+impl Default for Themes {
+    fn default() -> Self {
+        Self(config::themes::builtin())
+    }
+}
+
+impl Themes {
+    pub(crate) fn get(&self, id: &str) -> Option<&config::themes::Theme> {
+        self.0.iter().find(|theme| theme.id == id)
+    }
+}
+
+/// The palette in use. Changing it recolours everything tagged with
+/// [`ThemeColor`] or [`ThemeBorder`].
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub(crate) struct Theme {
+    colors: config::ColorConfig,
+}
+
+/// Which palette entry an entity is drawn in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Role {
+    Text,
+    Background,
+    MenuBg,
+    MenuButtonBg,
+    MenuButtonBorder,
+    GameOverBg,
+    GameOverText,
+    GameOverButtonBg,
+    GameOverExitBg,
+    GameOverButtonBorder,
+    Snake,
+    Food,
+    Border,
+    ButtonHover,
+    ButtonPressed,
+    FocusBorder,
+    Crash,
+}
+
+/// Colours an entity's `Sprite`, `TextColor` or `BackgroundColor`.
+#[derive(Component, Debug, Copy, Clone)]
+pub(crate) struct ThemeColor(pub(crate) Role);
+
+/// Colours an entity's `BorderColor`.
+#[derive(Component, Debug, Copy, Clone)]
+pub(crate) struct ThemeBorder(pub(crate) Role);
+
+impl Theme {
+    /// The theme named in `config.display.theme`. The classic theme takes its
+    /// colours from the config file's `[colors]` section so they can still be
+    /// overridden one at a time; an unknown name falls back to classic.
+    pub(crate) fn select(config: &config::Config, themes: &Themes) -> Self {
+        let colors = match themes.get(&config.display.theme) {
+            Some(theme) if theme.id != config::themes::CLASSIC => theme.colors.clone(),
+            _ => config.colors.clone(),
+        };
+        Self { colors }
+    }
+
+    pub(crate) fn color(&self, role: Role) -> Color {
+        let colors = &self.colors;
+        let (r, g, b) = match role {
+            Role::Text => colors.white,
+            Role::Background => colors.background,
+            Role::MenuBg => colors.menu_bg,
+            Role::MenuButtonBg => colors.menu_button_bg,
+            Role::MenuButtonBorder => colors.menu_button_border,
+            Role::GameOverBg => colors.game_over_bg,
+            Role::GameOverText => colors.game_over_text,
+            Role::GameOverButtonBg => colors.game_over_button_bg,
+            Role::GameOverExitBg => colors.game_over_exit_bg,
+            Role::GameOverButtonBorder => colors.game_over_button_border,
+            Role::Snake => colors.snake,
+            Role::Food => colors.food,
+            Role::Border => colors.border,
+            Role::ButtonHover => colors.button_hover,
+            Role::ButtonPressed => colors.button_pressed,
+            Role::FocusBorder => colors.focus_border,
+            Role::Crash => colors.crash,
+        };
+        Color::srgb(r, g, b)
+    }
+
+    pub(crate) fn text(&self, role: Role) -> (TextColor, ThemeColor) {
+        (TextColor(self.color(role)), ThemeColor(role))
+    }
+
+    pub(crate) fn background(&self, role: Role) -> (BackgroundColor, ThemeColor) {
+        (BackgroundColor(self.color(role)), ThemeColor(role))
+    }
+
+    pub(crate) fn border(&self, role: Role) -> (BorderColor, ThemeBorder) {
+        (BorderColor::all(self.color(role)), ThemeBorder(role))
+    }
+
+    pub(crate) fn sprite(&self, role: Role, size: Vec2) -> (Sprite, ThemeColor) {
+        (
+            Sprite {
+                color: self.color(role),
+                custom_size: Some(size),
+                ..default()
+            },
+            ThemeColor(role),
+        )
+    }
+}
+
+/// Recolours tagged entities and the clear colour after the theme changes,
+/// keeping each colour's transparency.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_theme(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut colored: Query<(
+        &ThemeColor,
+        Option<&mut Sprite>,
+        Option<&mut TextColor>,
+        Option<&mut BackgroundColor>,
+    )>,
+    mut bordered: Query<(&ThemeBorder, &mut BorderColor)>,
+) {
+    clear_color.0 = theme.color(Role::Background);
+    for (role, sprite, text, background) in &mut colored {
+        let color = theme.color(role.0);
+        if let Some(mut sprite) = sprite {
+            sprite.color = color.with_alpha(sprite.color.alpha());
+        }
+        if let Some(mut text) = text {
+            text.0 = color.with_alpha(text.0.alpha());
+        }
+        if let Some(mut background) = background {
+            background.0 = color.with_alpha(background.0.alpha());
+        }
+    }
+    for (role, mut border) in &mut bordered {
+        *border = BorderColor::all(theme.color(role.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_uses_config_colors_and_unknown_falls_back() {
+        let themes = Themes::default();
+        let mut config = config::Config::default();
+        config.colors.snake = (0.0, 0.0, 1.0);
+
+        assert_eq!(
+            Theme::select(&config, &themes).color(Role::Snake),
+            Color::srgb(0.0, 0.0, 1.0)
+        );
+        config.display.theme = "missing".to_string();
+        assert_eq!(
+            Theme::select(&config, &themes).color(Role::Snake),
+            Color::srgb(0.0, 0.0, 1.0)
+        );
+        config.display.theme = "gameboy".to_string();
+        assert_ne!(
+            Theme::select(&config, &themes).color(Role::Snake),
+            Color::srgb(0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn changing_the_theme_recolours_tagged_entities() {
+        let mut app = App::new();
+        app.init_resource::<Theme>();
+        app.init_resource::<ClearColor>();
+        app.add_systems(Update, apply_theme.run_if(resource_changed::<Theme>));
+        let theme = Theme::default();
+        let text = app.world_mut().spawn(theme.text(Role::Text)).id();
+        let button = app
+            .world_mut()
+            .spawn((
+                theme.background(Role::MenuButtonBg),
+                theme.border(Role::MenuButtonBorder),
+            ))
+            .id();
+        app.update();
+
+        let themes = Themes::default();
+        let mut config = config::Config::default();
+        config.display.theme = "gameboy".to_string();
+        let gameboy = Theme::select(&config, &themes);
+        app.insert_resource(gameboy.clone());
+        app.update();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<TextColor>(text).map(|c| c.0),
+            Some(gameboy.color(Role::Text))
+        );
+        assert_eq!(
+            world.get::<BackgroundColor>(button).map(|c| c.0),
+            Some(gameboy.color(Role::MenuButtonBg))
+        );
+        assert_eq!(
+            world.get::<BorderColor>(button).map(|c| c.top),
+            Some(gameboy.color(Role::MenuButtonBorder))
+        );
+        assert_eq!(
+            world.resource::<ClearColor>().0,
+            gameboy.color(Role::Background)
+        );
+    }
+}
@@ -0,0 +1,515 @@
+//! Building blocks for menu screens.
+//!
+//! A screen is spawned with [`spawn_screen`] or [`spawn_overlay`] and filled
+//! in through a [`Ui`], which lays widgets out in a column and colours them
+//! from the [`Theme`]. Only the root entity carries the screen's marker, so
+//! [`despawn_screen`] removes the whole screen.
+//!
+//! Buttons carry an action of the screen's own type. [`dispatch`] turns a
+//! press into a [`Pressed`] message, so a screen's input system reads
+//! `MessageReader<Pressed<Action>>` instead of querying `Interaction`; each
+//! action type is registered once with [`WidgetApp::add_actions`]. Toggles
+//! and sliders keep their state in a component and redraw themselves when it
+//! changes.
+
+use bevy::prelude::*;
+use bevy::ecs::system::EntityCommands;
+use beavy_config as config;
+
+use crate::focus::navigate_buttons;
+use crate::state::ConfigResource;
+use crate::theme::{Role, Theme};
+
+/// Sent when a button carrying the action `A` is pressed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Pressed<A>(pub(crate) A);
+
+impl<A: Send + Sync + 'static> Message for Pressed<A> {}
+
+pub(crate) trait WidgetApp {
+    /// Sends [`Pressed<A>`] whenever a button with an `A` component is pressed.
+    fn add_actions<A: Component + Copy>(&mut self) -> &mut Self;
+}
+
+impl WidgetApp for App {
+    fn add_actions<A: Component + Copy>(&mut self) -> &mut Self {
+        self.add_message::<Pressed<A>>()
+            .add_systems(PreUpdate, dispatch::<A>.after(navigate_buttons))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn dispatch<A: Component + Copy>(
+    interactions: Query<(&Interaction, &A), (Changed<Interaction>, With<Button>)>,
+    mut pressed: MessageWriter<Pressed<A>>,
+) {
+    for (interaction, action) in &interactions {
+        if *interaction == Interaction::Pressed {
+            pressed.write(Pressed(*action));
+        }
+    }
+}
+
+/// An on/off button; its label follows the value.
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Toggle(pub(crate) bool);
+
+/// A bar filled to `fraction` (0.0 to 1.0) with `text` written over it.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub(crate) struct Slider {
+    pub(crate) fraction: f32,
+    pub(crate) text: String,
+}
+
+#[derive(Component)]
+pub(crate) struct SliderFill;
+
+/// Spawns a full-window screen whose widgets sit in a centred column over
+/// `background`.
+pub(crate) fn spawn_screen(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    background: Role,
+    root: impl Bundle,
+    build: impl FnOnce(&mut Ui),
+) -> Entity {
+    let mut screen = commands.spawn((
+        Node {
+            width: percent(config::ui::ROOT_PERCENT),
+            height: percent(config::ui::ROOT_PERCENT),
+            justify_content: JustifyContent::Center,
+            ..column(config)
+        },
+        theme.background(background),
+        root,
+    ));
+    screen.with_children(|parent| build(&mut Ui::new(parent, config, theme)));
+    screen.id()
+}
+
+/// Spawns a bordered panel centred over whatever screen is showing.
+pub(crate) fn spawn_overlay(
+    commands: &mut Commands,
+    config: &config::Config,
+    theme: &Theme,
+    root: impl Bundle,
+    build: impl FnOnce(&mut Ui),
+) -> Entity {
+    let mut overlay = commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(config::ui::ROOT_PERCENT),
+            height: percent(config::ui::ROOT_PERCENT),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        root,
+    ));
+    overlay.with_children(|parent| {
+        parent
+            .spawn((
+                Node {
+                    padding: UiRect::all(px(config.ui.panel_gap * 2.0)),
+                    border: UiRect::all(px(config.ui.button_border)),
+                    ..column(config)
+                },
+                theme.background(Role::MenuBg),
+                theme.border(Role::MenuButtonBorder),
+            ))
+            .with_children(|panel| build(&mut Ui::new(panel, config, theme)));
+    });
+    overlay.id()
+}
+
+pub(crate) fn despawn_screen<M: Component>(
+    mut commands: Commands,
+    roots: Query<Entity, (With<M>, Without<ChildOf>)>,
+) {
+    for entity in &roots {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn column(config: &config::Config) -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        row_gap: px(config.ui.panel_gap),
+        ..default()
+    }
+}
+
+/// Spawns widgets into one container of a screen.
+pub(crate) struct Ui<'a, 'w> {
+    parent: &'a mut ChildSpawnerCommands<'w>,
+    pub(crate) config: &'a config::Config,
+    pub(crate) theme: &'a Theme,
+    /// Background and border roles of the buttons spawned from here on.
+    button_roles: (Role, Role),
+}
+
+impl<'a, 'w> Ui<'a, 'w> {
+    fn new(
+        parent: &'a mut ChildSpawnerCommands<'w>,
+        config: &'a config::Config,
+        theme: &'a Theme,
+    ) -> Self {
+        Self {
+            parent,
+            config,
+            theme,
+            button_roles: (Role::MenuButtonBg, Role::MenuButtonBorder),
+        }
+    }
+
+    /// Colours the buttons spawned after this call.
+    pub(crate) fn button_roles(&mut self, background: Role, border: Role) {
+        self.button_roles = (background, border);
+    }
+
+    /// Spawns `node` and fills it through a nested [`Ui`].
+    pub(crate) fn container(
+        &mut self,
+        node: Node,
+        build: impl FnOnce(&mut Ui),
+    ) -> EntityCommands<'_> {
+        let (config, theme, button_roles) = (self.config, self.theme, self.button_roles);
+        let mut container = self.parent.spawn(node);
+        container.with_children(|parent| {
+            build(&mut Ui {
+                parent,
+                config,
+                theme,
+                button_roles,
+            })
+        });
+        container
+    }
+
+    /// Lays widgets out side by side.
+    pub(crate) fn row(&mut self, build: impl FnOnce(&mut Ui)) -> EntityCommands<'_> {
+        let node = Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: px(self.config.ui.panel_gap),
+            ..default()
+        };
+        self.container(node, build)
+    }
+
+    /// A row with `label` in a fixed-width column before the widgets, so the
+    /// widgets of consecutive fields line up.
+    pub(crate) fn field(&mut self, label: &str, build: impl FnOnce(&mut Ui)) -> EntityCommands<'_> {
+        self.row(|ui| {
+            ui.label(label).insert(Node {
+                width: px(config::ui::SETTING_LABEL_WIDTH),
+                ..default()
+            });
+            build(ui);
+        })
+    }
+
+    pub(crate) fn text(
+        &mut self,
+        text: impl Into<String>,
+        font_size: f32,
+        role: Role,
+    ) -> EntityCommands<'_> {
+        let color = self.theme.text(role);
+        self.parent.spawn((
+            Text::new(text),
+            TextFont {
+                font_size,
+                ..default()
+            },
+            color,
+        ))
+    }
+
+    pub(crate) fn title(&mut self, text: &str) -> EntityCommands<'_> {
+        self.text(text, self.config.ui.title_font_size, Role::Text)
+    }
+
+    pub(crate) fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
+        self.text(text, self.config.ui.button_font_size, Role::Text)
+    }
+
+    /// A standard-size button that sends `action` when pressed.
+    pub(crate) fn button<A: Component>(&mut self, label: &str, action: A) -> EntityCommands<'_> {
+        let size = (
+            px(self.config.ui.button_width),
+            px(self.config.ui.button_height),
+        );
+        self.sized_button(label, size, action)
+    }
+
+    pub(crate) fn sized_button<A: Component>(
+        &mut self,
+        label: &str,
+        (width, height): (Val, Val),
+        action: A,
+    ) -> EntityCommands<'_> {
+        let (background, border) = self.button_roles;
+        let node = Node {
+            width,
+            height,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(px(self.config.ui.button_border)),
+            ..default()
+        };
+        let label = (
+            Text::new(label),
+            TextFont {
+                font_size: self.config.ui.button_font_size,
+                ..default()
+            },
+            self.theme.text(Role::Text),
+        );
+        let mut button = self.parent.spawn((
+            Button,
+            node,
+            self.theme.border(border),
+            self.theme.background(background),
+            action,
+        ));
+        button.with_child(label);
+        button
+    }
+
+    /// A column of standard buttons.
+    pub(crate) fn list<'s, A: Component>(
+        &mut self,
+        items: impl IntoIterator<Item = (&'s str, A)>,
+    ) -> EntityCommands<'_> {
+        let node = column(self.config);
+        self.container(node, |ui| {
+            for (label, action) in items {
+                ui.button(label, action);
+            }
+        })
+    }
+
+    /// Arrows either side of `value`. Returns the value's text entity.
+    pub(crate) fn choice<A: Component>(
+        &mut self,
+        value: &str,
+        previous: A,
+        next: A,
+    ) -> EntityCommands<'_> {
+        let arrow = self.arrow_size();
+        let width = px(self.config.ui.button_width);
+        self.sized_button("<", arrow, previous);
+        let value = self
+            .label(value)
+            .insert((
+                TextLayout::new_with_justify(Justify::Center),
+                Node { width, ..default() },
+            ))
+            .id();
+        self.sized_button(">", arrow, next);
+        self.parent.commands_mut().entity(value)
+    }
+
+    /// A button whose label reads on or off.
+    pub(crate) fn toggle<A: Component>(&mut self, on: bool, action: A) -> EntityCommands<'_> {
+        let label = toggle_label(&self.config.text, on).to_string();
+        let mut toggle = self.button(&label, action);
+        toggle.insert(Toggle(on));
+        toggle
+    }
+
+    /// A filled bar between arrows that step it. Returns the bar's entity.
+    pub(crate) fn slider<A: Component>(
+        &mut self,
+        slider: Slider,
+        decrease: A,
+        increase: A,
+    ) -> EntityCommands<'_> {
+        let arrow = self.arrow_size();
+        let (background, border) = self.button_roles;
+        self.sized_button("<", arrow, decrease);
+        let fill = (
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                width: percent(slider.fraction * config::ui::ROOT_PERCENT),
+                height: percent(config::ui::ROOT_PERCENT),
+                ..default()
+            },
+            self.theme.background(Role::ButtonPressed),
+            SliderFill,
+        );
+        let label = (
+            Text::new(slider.text.as_str()),
+            TextFont {
+                font_size: self.config.ui.button_font_size,
+                ..default()
+            },
+            self.theme.text(Role::Text),
+        );
+        let bar = self
+            .parent
+            .spawn((
+                Node {
+                    width: px(self.config.ui.button_width),
+                    height: px(self.config.ui.button_height),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(px(self.config.ui.button_border)),
+                    ..default()
+                },
+                self.theme.background(background),
+                self.theme.border(border),
+                slider,
+            ))
+            .with_children(|bar| {
+                bar.spawn(fill);
+                bar.spawn(label);
+            })
+            .id();
+        self.sized_button(">", arrow, increase);
+        self.parent.commands_mut().entity(bar)
+    }
+
+    fn arrow_size(&self) -> (Val, Val) {
+        (
+            px(config::ui::SETTING_ARROW_WIDTH),
+            px(self.config.ui.button_height),
+        )
+    }
+}
+
+fn toggle_label(text: &config::TextConfig, on: bool) -> &str {
+    if on {
+        &text.on
+    } else {
+        &text.off
+    }
+}
+
+pub(crate) fn sync_toggles(
+    config: Res<ConfigResource>,
+    toggles: Query<(&Toggle, &Children), Changed<Toggle>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (toggle, children) in &toggles {
+        let mut labels = texts.iter_many_mut(children);
+        while let Some(mut text) = labels.fetch_next() {
+            **text = toggle_label(&config.0.text, toggle.0).to_string();
+        }
+    }
+}
+
+pub(crate) fn sync_sliders(
+    sliders: Query<(&Slider, &Children), Changed<Slider>>,
+    mut fills: Query<&mut Node, With<SliderFill>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (slider, children) in &sliders {
+        for child in children {
+            if let Ok(mut fill) = fills.get_mut(*child) {
+                fill.width = percent(slider.fraction.clamp(0.0, 1.0) * config::ui::ROOT_PERCENT);
+            }
+            if let Ok(mut text) = texts.get_mut(*child) {
+                **text = slider.text.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Debug, Copy, Clone, PartialEq)]
+    enum TestAction {
+        Go,
+        Stop,
+    }
+
+    #[derive(Component)]
+    struct TestRoot;
+
+    fn spawn(app: &mut App, build: impl FnOnce(&mut Ui)) {
+        let config = ConfigResource::default();
+        let mut commands = app.world_mut().commands();
+        spawn_screen(
+            &mut commands,
+            &config.0,
+            &Theme::default(),
+            Role::MenuBg,
+            TestRoot,
+            build,
+        );
+        app.world_mut().flush();
+    }
+
+    #[test]
+    fn pressing_a_button_sends_its_action() {
+        let mut app = App::new();
+        app.add_actions::<TestAction>();
+        spawn(&mut app, |ui| {
+            ui.list([("Go", TestAction::Go), ("Stop", TestAction::Stop)]);
+        });
+
+        let world = app.world_mut();
+        let stop = world
+            .query::<(Entity, &TestAction)>()
+            .iter(world)
+            .find(|(_, action)| **action == TestAction::Stop)
+            .map(|(entity, _)| entity)
+            .expect("button spawned");
+        world.entity_mut(stop).insert(Interaction::Pressed);
+        app.update();
+
+        let messages = app.world().resource::<Messages<Pressed<TestAction>>>();
+        let sent: Vec<_> = messages.get_cursor().read(messages).copied().collect();
+        assert_eq!(sent, [Pressed(TestAction::Stop)]);
+    }
+
+    #[test]
+    fn widgets_redraw_when_their_state_changes() {
+        let mut app = App::new();
+        app.init_resource::<ConfigResource>();
+        app.add_systems(Update, (sync_toggles, sync_sliders));
+        spawn(&mut app, |ui| {
+            ui.toggle(false, TestAction::Go);
+            ui.slider(
+                Slider {
+                    fraction: 0.25,
+                    text: "25%".to_string(),
+                },
+                TestAction::Stop,
+                TestAction::Go,
+            );
+        });
+        app.update();
+
+        let world = app.world_mut();
+        for mut toggle in world.query::<&mut Toggle>().iter_mut(world) {
+            toggle.0 = true;
+        }
+        for mut slider in world.query::<&mut Slider>().iter_mut(world) {
+            slider.fraction = 0.75;
+            slider.text = "75%".to_string();
+        }
+        app.update();
+
+        let world = app.world_mut();
+        let texts: Vec<String> = world
+            .query::<&Text>()
+            .iter(world)
+            .map(|text| text.0.clone())
+            .collect();
+        let fill = world
+            .query_filtered::<&Node, With<SliderFill>>()
+            .single(world)
+            .expect("one slider");
+        assert!(texts.contains(&config::text::ON.to_string()));
+        assert!(texts.contains(&"75%".to_string()));
+        assert_eq!(fill.width, percent(75.0));
+    }
+}
@@ -0,0 +1,120 @@
+//! A simple bot for headless runs and demos.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{set_direction, step, Direction, GameState, GridPos};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// Picks the move that heads for the food without walking into a dead end.
+///
+/// Each candidate is simulated one step ahead. Moves that survive and leave
+/// enough room for the body are preferred, then moves that eat, then moves
+/// closer to the food.
+pub fn choose_direction(state: &GameState) -> Direction {
+    DIRECTIONS
+        .into_iter()
+        .filter(|direction| *direction != state.direction.opposite())
+        .max_by_key(|direction| {
+            let mut next = state.clone();
+            set_direction(&mut next, *direction);
+            step(&mut next);
+            if !next.alive {
+                return (false, false, 0, i32::MIN);
+            }
+            let room = reachable_cells(&next) >= next.snake.len();
+            let ate = next.score > state.score;
+            (
+                true,
+                room,
+                u8::from(ate),
+                -distance(&next, next.snake[0], next.food),
+            )
+        })
+        .unwrap_or(state.direction)
+}
+
+/// Plays until the snake dies or `max_ticks` is reached.
+pub fn play_bot(state: &mut GameState, max_ticks: u64) {
+    while state.alive && state.tick < max_ticks {
+        let direction = choose_direction(state);
+        set_direction(state, direction);
+        step(state);
+    }
+}
+
+fn reachable_cells(state: &GameState) -> usize {
+    let blocked: HashSet<GridPos> = state.snake.iter().skip(1).copied().collect();
+    let mut seen = HashSet::from([state.snake[0]]);
+    let mut queue = VecDeque::from([state.snake[0]]);
+    while let Some(cell) = queue.pop_front() {
+        for direction in DIRECTIONS {
+            let Some(next) = neighbour(state, cell, direction) else {
+                continue;
+            };
+            if !blocked.contains(&next) && !state.is_obstacle(next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen.len()
+}
+
+fn neighbour(state: &GameState, cell: GridPos, direction: Direction) -> Option<GridPos> {
+    let (dx, dy) = match direction {
+        Direction::Up => (0, 1),
+        Direction::Down => (0, -1),
+        Direction::Left => (-1, 0),
+        Direction::Right => (1, 0),
+    };
+    let mut next = GridPos {
+        x: cell.x + dx,
+        y: cell.y + dy,
+    };
+    if state.rules.wrap_walls {
+        next.x = next.x.rem_euclid(state.grid_width);
+        next.y = next.y.rem_euclid(state.grid_height);
+    }
+    let inside =
+        next.x >= 0 && next.x < state.grid_width && next.y >= 0 && next.y < state.grid_height;
+    inside.then_some(next)
+}
+
+fn distance(state: &GameState, a: GridPos, b: GridPos) -> i32 {
+    let mut dx = (a.x - b.x).abs();
+    let mut dy = (a.y - b.y).abs();
+    if state.rules.wrap_walls {
+        dx = dx.min(state.grid_width - dx);
+        dy = dy.min(state.grid_height - dy);
+    }
+    dx + dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_seeded_game, Rules};
+
+    #[test]
+    fn bot_avoids_walls() {
+        let mut state = new_seeded_game(10, 10, 1, Rules::default());
+        state.snake = vec![GridPos { x: 9, y: 5 }];
+        state.food = GridPos { x: 0, y: 0 };
+
+        assert_ne!(choose_direction(&state), Direction::Right);
+    }
+
+    #[test]
+    fn bot_scores_on_an_open_board() {
+        let mut state = new_seeded_game(12, 12, 4, Rules::default());
+
+        play_bot(&mut state, 2_000);
+
+        assert!(state.score >= 10, "bot only scored {}", state.score);
+    }
+}
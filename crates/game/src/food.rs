@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::rng::GameRng;
+use crate::{GameState, GridPos};
+
+/// Chooses where the next piece of food appears.
+pub trait FoodSpawner {
+    /// Returns a free cell for the next food, or `None` when the snake covers
+    /// the whole board.
+    fn spawn(&self, state: &GameState, rng: &mut GameRng) -> Option<GridPos>;
+}
+
+/// Built-in spawners, stored in [`crate::Rules`] so the choice travels with
+/// saves and replays.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum FoodStrategy {
+    /// Any free cell with equal probability.
+    #[default]
+    Uniform,
+    /// A free cell at least `min_distance` steps from the head, if one exists.
+    Fair { min_distance: i32 },
+    /// Cells in a fixed order for puzzle levels; the `n`th food uses entry
+    /// `n % len`, skipping entries covered by the snake.
+    Sequence(Vec<GridPos>),
+    /// Free cells weighted towards the walls.
+    EdgeBiased,
+}
+
+impl FoodSpawner for FoodStrategy {
+    fn spawn(&self, state: &GameState, rng: &mut GameRng) -> Option<GridPos> {
+        match self {
+            Self::Uniform => UniformSpawner.spawn(state, rng),
+            Self::Fair { min_distance } => FairSpawner {
+                min_distance: *min_distance,
+            }
+            .spawn(state, rng),
+            Self::Sequence(cells) => SequenceSpawner { cells }.spawn(state, rng),
+            Self::EdgeBiased => EdgeBiasedSpawner.spawn(state, rng),
+        }
+    }
+}
+
+pub struct UniformSpawner;
+
+impl FoodSpawner for UniformSpawner {
+    fn spawn(&self, state: &GameState, rng: &mut GameRng) -> Option<GridPos> {
+        pick(&free_cells(state), rng)
+    }
+}
+
+pub struct FairSpawner {
+    pub min_distance: i32,
+}
+
+impl FoodSpawner for FairSpawner {
+    fn spawn(&self, state: &GameState, rng: &mut GameRng) -> Option<GridPos> {
+        let free = free_cells(state);
+        let head = state.snake.first().copied()?;
+        let far: Vec<GridPos> = free
+            .iter()
+            .copied()
+            .filter(|cell| distance(state, head, *cell) >= self.min_distance)
+            .collect();
+        if far.is_empty() {
+            pick(&free, rng)
+        } else {
+            pick(&far, rng)
+        }
+    }
+}
+
+pub struct SequenceSpawner<'a> {
+    pub cells: &'a [GridPos],
+}
+
+impl FoodSpawner for SequenceSpawner<'_> {
+    fn spawn(&self, state: &GameState, rng: &mut GameRng) -> Option<GridPos> {
+        let len = self.cells.len();
+        let start = state.score as usize;
+        let occupied = occupied(state);
+        (0..len)
+            .map(|offset| self.cells[(start + offset) % len])
+            .find(|cell| is_free(state, &occupied, *cell))
+            .or_else(|| UniformSpawner.spawn(state, rng))
+    }
+}
+
+pub struct EdgeBiasedSpawner;
+
+impl FoodSpawner for EdgeBiasedSpawner {
+    fn spawn(&self, state: &GameState, rng: &mut GameRng) -> Option<GridPos> {
+        let free = free_cells(state);
+        let rings = state.grid_width.min(state.grid_height).div_euclid(2) + 1;
+        let weight = |cell: &GridPos| {
+            let to_edge = cell
+                .x
+                .min(cell.y)
+                .min(state.grid_width - 1 - cell.x)
+                .min(state.grid_height - 1 - cell.y);
+            let closeness = (rings - to_edge).max(1) as u64;
+            closeness * closeness
+        };
+
+        let total: u64 = free.iter().map(weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for cell in &free {
+            let cell_weight = weight(cell);
+            if roll < cell_weight {
+                return Some(*cell);
+            }
+            roll -= cell_weight;
+        }
+        free.last().copied()
+    }
+}
+
+/// The cells covered by the snake or an obstacle, gathered once so checking
+/// every cell on the board stays linear in its size.
+fn occupied(state: &GameState) -> HashSet<GridPos> {
+    state
+        .snake
+        .iter()
+        .chain(&state.rules.obstacles)
+        .copied()
+        .collect()
+}
+
+fn free_cells(state: &GameState) -> Vec<GridPos> {
+    let occupied = occupied(state);
+    (0..state.grid_height)
+        .flat_map(|y| (0..state.grid_width).map(move |x| GridPos { x, y }))
+        .filter(|cell| !occupied.contains(cell))
+        .collect()
+}
+
+fn is_free(state: &GameState, occupied: &HashSet<GridPos>, cell: GridPos) -> bool {
+    cell.x >= 0
+        && cell.x < state.grid_width
+        && cell.y >= 0
+        && cell.y < state.grid_height
+        && !occupied.contains(&cell)
+}
+
+fn pick(cells: &[GridPos], rng: &mut GameRng) -> Option<GridPos> {
+    if cells.is_empty() {
+        None
+    } else {
+        Some(cells[rng.gen_range(0..cells.len())])
+    }
+}
+
+fn distance(state: &GameState, a: GridPos, b: GridPos) -> i32 {
+    let mut dx = (a.x - b.x).abs();
+    let mut dy = (a.y - b.y).abs();
+    if state.rules.wrap_walls {
+        dx = dx.min(state.grid_width - dx);
+        dy = dy.min(state.grid_height - dy);
+    }
+    dx + dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_seeded_game, Rules};
+    use rand::SeedableRng;
+
+    #[test]
+    fn fair_spawner_keeps_distance_from_head() {
+        let state = new_seeded_game(12, 12, 5, Rules::default());
+        let head = state.snake[0];
+        let mut rng = GameRng::seed_from_u64(1);
+
+        for _ in 0..50 {
+            let cell = FairSpawner { min_distance: 6 }
+                .spawn(&state, &mut rng)
+                .expect("free cell");
+            assert!(distance(&state, head, cell) >= 6);
+        }
+    }
+
+    #[test]
+    fn sequence_spawner_follows_score_and_skips_body() {
+        let mut state = new_seeded_game(8, 8, 5, Rules::default());
+        let cells = [GridPos { x: 1, y: 1 }, GridPos { x: 2, y: 2 }];
+        let mut rng = GameRng::seed_from_u64(1);
+        let spawner = SequenceSpawner { cells: &cells };
+
+        assert_eq!(spawner.spawn(&state, &mut rng), Some(cells[0]));
+        state.score = 1;
+        assert_eq!(spawner.spawn(&state, &mut rng), Some(cells[1]));
+        state.snake = vec![cells[1]];
+        assert_eq!(spawner.spawn(&state, &mut rng), Some(cells[0]));
+    }
+
+    #[test]
+    fn edge_biased_spawner_prefers_walls() {
+        let state = new_seeded_game(20, 20, 5, Rules::default());
+        let mut rng = GameRng::seed_from_u64(9);
+
+        let on_edge = (0..400)
+            .filter_map(|_| EdgeBiasedSpawner.spawn(&state, &mut rng))
+            .filter(|cell| cell.x == 0 || cell.y == 0 || cell.x == 19 || cell.y == 19)
+            .count();
+
+        // Edge cells are 19% of the board; uniform placement would land
+        // there roughly 76 times out of 400.
+        assert!(on_edge > 100, "only {on_edge} spawns on the edge");
+    }
+
+    #[test]
+    fn full_board_has_no_spawn() {
+        let mut state = new_seeded_game(2, 1, 5, Rules::default());
+        state.snake = vec![GridPos { x: 0, y: 0 }, GridPos { x: 1, y: 0 }];
+        let mut rng = GameRng::seed_from_u64(1);
+
+        assert_eq!(UniformSpawner.spawn(&state, &mut rng), None);
+    }
+}
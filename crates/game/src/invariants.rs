@@ -0,0 +1,264 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{Direction, FoodStrategy, GameState, GridPos};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InvariantError {
+    InvalidGrid,
+    EmptySnake,
+    SegmentOutOfBounds { index: usize, pos: GridPos },
+    SegmentsNotContiguous { index: usize },
+    DuplicateSegment { pos: GridPos },
+    FoodOutOfBounds { pos: GridPos },
+    FoodOnBody { pos: GridPos },
+    OnObstacle { pos: GridPos },
+    PendingReversesDirection,
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidGrid => write!(f, "grid dimensions must be positive"),
+            Self::EmptySnake => write!(f, "snake has no segments"),
+            Self::SegmentOutOfBounds { index, pos } => {
+                write!(
+                    f,
+                    "segment {index} at ({}, {}) is outside the grid",
+                    pos.x, pos.y
+                )
+            }
+            Self::SegmentsNotContiguous { index } => {
+                write!(f, "segment {index} is not adjacent to the one before it")
+            }
+            Self::DuplicateSegment { pos } => {
+                write!(f, "two segments share cell ({}, {})", pos.x, pos.y)
+            }
+            Self::FoodOutOfBounds { pos } => {
+                write!(f, "food at ({}, {}) is outside the grid", pos.x, pos.y)
+            }
+            Self::FoodOnBody { pos } => {
+                write!(f, "food at ({}, {}) overlaps the snake", pos.x, pos.y)
+            }
+            Self::OnObstacle { pos } => {
+                write!(f, "cell ({}, {}) is an obstacle", pos.x, pos.y)
+            }
+            Self::PendingReversesDirection => {
+                write!(f, "pending direction reverses the current direction")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+impl GameState {
+    /// Checks the structural invariants every reachable state must satisfy.
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if self.grid_width <= 0 || self.grid_height <= 0 {
+            return Err(InvariantError::InvalidGrid);
+        }
+        if self.snake.is_empty() {
+            return Err(InvariantError::EmptySnake);
+        }
+
+        let mut seen = HashSet::with_capacity(self.snake.len());
+        for (index, pos) in self.snake.iter().enumerate() {
+            if !self.in_bounds(*pos) {
+                return Err(InvariantError::SegmentOutOfBounds { index, pos: *pos });
+            }
+            if self.is_obstacle(*pos) {
+                return Err(InvariantError::OnObstacle { pos: *pos });
+            }
+            if !seen.insert(*pos) {
+                return Err(InvariantError::DuplicateSegment { pos: *pos });
+            }
+            if index > 0 && !self.adjacent(self.snake[index - 1], *pos) {
+                return Err(InvariantError::SegmentsNotContiguous { index });
+            }
+        }
+
+        if !self.in_bounds(self.food) {
+            return Err(InvariantError::FoodOutOfBounds { pos: self.food });
+        }
+        let blocked: HashSet<GridPos> = self
+            .rules
+            .obstacles
+            .iter()
+            .copied()
+            .filter(|pos| self.in_bounds(*pos))
+            .collect();
+        let open_cells =
+            i64::from(self.grid_width) * i64::from(self.grid_height) - blocked.len() as i64;
+        let board_full = self.snake.len() as i64 >= open_cells;
+        if seen.contains(&self.food) && !board_full {
+            return Err(InvariantError::FoodOnBody { pos: self.food });
+        }
+        if self.is_obstacle(self.food) && !board_full {
+            return Err(InvariantError::OnObstacle { pos: self.food });
+        }
+
+        if self.pending_direction == self.direction.opposite() {
+            return Err(InvariantError::PendingReversesDirection);
+        }
+        Ok(())
+    }
+
+    /// FNV-1a hash over every field, including the RNG state. The byte layout
+    /// is fixed so hashes can be compared across platforms and releases.
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        hasher.write_i32(self.grid_width);
+        hasher.write_i32(self.grid_height);
+        hasher.write_u64(self.seed);
+        hasher.write_u8(u8::from(self.rules.wrap_walls));
+        hasher.write_food(&self.rules.food);
+        hasher.write_u64(self.rules.obstacles.len() as u64);
+        for obstacle in &self.rules.obstacles {
+            hasher.write_pos(*obstacle);
+        }
+        hasher.write_u64(self.rng.state());
+        hasher.write_u64(self.tick);
+        hasher.write_u64(self.snake.len() as u64);
+        for segment in &self.snake {
+            hasher.write_pos(*segment);
+        }
+        hasher.write_u8(direction_byte(self.direction));
+        hasher.write_u8(direction_byte(self.pending_direction));
+        hasher.write_pos(self.food);
+        hasher.write_u32(self.score);
+        hasher.write_u8(u8::from(self.alive));
+        hasher.finish()
+    }
+
+    fn in_bounds(&self, pos: GridPos) -> bool {
+        pos.x >= 0 && pos.x < self.grid_width && pos.y >= 0 && pos.y < self.grid_height
+    }
+
+    fn adjacent(&self, a: GridPos, b: GridPos) -> bool {
+        let mut dx = (a.x - b.x).abs();
+        let mut dy = (a.y - b.y).abs();
+        if self.rules.wrap_walls {
+            dx = dx.min(self.grid_width - dx);
+            dy = dy.min(self.grid_height - dy);
+        }
+        dx + dy == 1
+    }
+}
+
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_pos(&mut self, pos: GridPos) {
+        self.write_i32(pos.x);
+        self.write_i32(pos.y);
+    }
+
+    fn write_food(&mut self, food: &FoodStrategy) {
+        match food {
+            FoodStrategy::Uniform => self.write_u8(0),
+            FoodStrategy::Fair { min_distance } => {
+                self.write_u8(1);
+                self.write_i32(*min_distance);
+            }
+            FoodStrategy::Sequence(cells) => {
+                self.write_u8(2);
+                self.write_u64(cells.len() as u64);
+                for cell in cells {
+                    self.write_pos(*cell);
+                }
+            }
+            FoodStrategy::EdgeBiased => self.write_u8(3),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn direction_byte(direction: Direction) -> u8 {
+    match direction {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_seeded_game, Rules};
+
+    #[test]
+    fn new_game_is_valid() {
+        let state = new_seeded_game(8, 6, 3, Rules::default());
+
+        assert_eq!(state.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_broken_bodies() {
+        let mut state = new_seeded_game(8, 6, 3, Rules::default());
+        state.food = GridPos { x: 0, y: 0 };
+
+        state.snake = vec![GridPos { x: 3, y: 3 }, GridPos { x: 5, y: 3 }];
+        assert_eq!(
+            state.validate(),
+            Err(InvariantError::SegmentsNotContiguous { index: 1 })
+        );
+
+        state.snake = vec![GridPos { x: 3, y: 3 }, GridPos { x: 3, y: 3 }];
+        assert_eq!(
+            state.validate(),
+            Err(InvariantError::DuplicateSegment {
+                pos: GridPos { x: 3, y: 3 }
+            })
+        );
+
+        state.snake = vec![GridPos { x: 0, y: 0 }];
+        assert_eq!(
+            state.validate(),
+            Err(InvariantError::FoodOnBody {
+                pos: GridPos { x: 0, y: 0 }
+            })
+        );
+    }
+
+    #[test]
+    fn stable_hash_tracks_every_field() {
+        let state = new_seeded_game(8, 6, 3, Rules::default());
+        let mut turned = state.clone();
+        turned.pending_direction = Direction::Up;
+
+        assert_eq!(state.stable_hash(), state.clone().stable_hash());
+        assert_ne!(state.stable_hash(), turned.stable_hash());
+    }
+}
@@ -0,0 +1,159 @@
+//! Level files.
+//!
+//! A level is a plain-text grid, one line per row with the top row first:
+//!
+//! | tile | meaning                                          |
+//! |------|--------------------------------------------------|
+//! | `.`  | open cell                                        |
+//! | `#`  | wall                                             |
+//! | `o`  | open cell that food visits, in reading order     |
+//!
+//! Blank lines and lines starting with `;` are ignored. Every row must have
+//! the same width. The snake starts at the open cell nearest the centre.
+
+use std::fmt;
+
+use crate::{FoodStrategy, GridPos, Rules};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Level {
+    pub width: i32,
+    pub height: i32,
+    pub walls: Vec<GridPos>,
+    pub food: Vec<GridPos>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LevelError {
+    Empty,
+    RaggedRow {
+        line: usize,
+    },
+    UnknownTile {
+        line: usize,
+        column: usize,
+        tile: char,
+    },
+    NoOpenCell,
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "level has no rows"),
+            Self::RaggedRow { line } => {
+                write!(f, "line {line}: row width differs from the first row")
+            }
+            Self::UnknownTile { line, column, tile } => {
+                write!(f, "line {line}, column {column}: unknown tile {tile:?}")
+            }
+            Self::NoOpenCell => write!(f, "level has no open cell for the snake"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl Level {
+    pub fn parse(text: &str) -> Result<Self, LevelError> {
+        let rows: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_end()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with(';'))
+            .collect();
+        let width = rows.first().ok_or(LevelError::Empty)?.1.chars().count();
+        let height = rows.len();
+
+        let mut walls = Vec::new();
+        let mut food = Vec::new();
+        for (row, (line, text)) in rows.iter().enumerate() {
+            if text.chars().count() != width {
+                return Err(LevelError::RaggedRow { line: *line });
+            }
+            let y = (height - 1 - row) as i32;
+            for (column, tile) in text.chars().enumerate() {
+                let pos = GridPos {
+                    x: column as i32,
+                    y,
+                };
+                match tile {
+                    '.' => {}
+                    '#' => walls.push(pos),
+                    'o' => food.push(pos),
+                    _ => {
+                        return Err(LevelError::UnknownTile {
+                            line: *line,
+                            column: column + 1,
+                            tile,
+                        })
+                    }
+                }
+            }
+        }
+        if walls.len() == width * height {
+            return Err(LevelError::NoOpenCell);
+        }
+
+        Ok(Self {
+            width: width as i32,
+            height: height as i32,
+            walls,
+            food,
+        })
+    }
+
+    /// `base` with this level's walls, and its food order when it has one.
+    pub fn rules(&self, base: Rules) -> Rules {
+        let food = if self.food.is_empty() {
+            base.food
+        } else {
+            FoodStrategy::Sequence(self.food.clone())
+        };
+        Rules {
+            food,
+            obstacles: self.walls.clone(),
+            ..base
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::new_seeded_game;
+
+    #[test]
+    fn parse_reads_walls_and_food_bottom_up() {
+        let level = Level::parse("; test level\n#o.\n...\n\n..#\n").expect("valid level");
+
+        assert_eq!((level.width, level.height), (3, 3));
+        assert_eq!(
+            level.walls,
+            vec![GridPos { x: 0, y: 2 }, GridPos { x: 2, y: 0 }]
+        );
+        assert_eq!(level.food, vec![GridPos { x: 1, y: 2 }]);
+
+        let state = new_seeded_game(3, 3, 1, level.rules(Rules::default()));
+        assert_eq!(state.food, GridPos { x: 1, y: 2 });
+        assert_eq!(state.validate(), Ok(()));
+    }
+
+    #[test]
+    fn parse_reports_bad_layouts() {
+        assert_eq!(Level::parse("; nothing\n"), Err(LevelError::Empty));
+        assert_eq!(
+            Level::parse("...\n..\n"),
+            Err(LevelError::RaggedRow { line: 2 })
+        );
+        assert_eq!(
+            Level::parse("..x\n"),
+            Err(LevelError::UnknownTile {
+                line: 1,
+                column: 3,
+                tile: 'x'
+            })
+        );
+        assert_eq!(Level::parse("##\n"), Err(LevelError::NoOpenCell));
+    }
+}
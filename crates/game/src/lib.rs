@@ -1,5 +1,22 @@
+mod bot;
+mod food;
+mod invariants;
+mod level;
+mod replay;
+mod rng;
 mod state;
 mod update;
 
-pub use state::{Direction, GameState, GridPos};
-pub use update::{new_game, set_direction, step, StepResult};
+pub use bot::{choose_direction, play_bot};
+pub use food::{
+    EdgeBiasedSpawner, FairSpawner, FoodSpawner, FoodStrategy, SequenceSpawner, UniformSpawner,
+};
+pub use invariants::InvariantError;
+pub use level::{Level, LevelError};
+pub use replay::{
+    Replay, ReplayError, ReplayInput, ReplayRecorder, GAME_VERSION, REPLAY_FORMAT_VERSION,
+    REPLAY_MAGIC,
+};
+pub use rng::GameRng;
+pub use state::{Direction, GameState, GridPos, Rules};
+pub use update::{crash_cell, new_game, new_seeded_game, set_direction, step, StepResult};
@@ -0,0 +1,710 @@
+//! Replay files.
+//!
+//! A replay stores everything needed to re-simulate a run: the seed, rule
+//! set and grid size it started from, plus every accepted direction change
+//! keyed by the tick it was made on. Two encodings share the same fields:
+//!
+//! Binary (little-endian):
+//!
+//! | field          | encoding                                          |
+//! |----------------|---------------------------------------------------|
+//! | magic          | `b"BSNR"`                                         |
+//! | format version | `u16`                                             |
+//! | game version   | `u8` length + UTF-8 bytes                         |
+//! | seed           | `u64`                                             |
+//! | rules          | `u8` flags (bit 0: wrap walls)                    |
+//! | food strategy  | `u8` tag, then `i32` distance (fair) or varint    |
+//! |                | count + `u16` x/y pairs (sequence)                |
+//! | obstacles      | varint count + `u16` x/y pairs                    |
+//! | grid size      | `u16` width, `u16` height                         |
+//! | ticks          | varint                                            |
+//! | final score    | `u32`                                             |
+//! | state hash     | `u64`                                             |
+//! | inputs         | varint count, then varint `(tick delta << 2) | dir` |
+//! | checksum       | `u32` FNV-1a of every preceding byte              |
+//!
+//! JSON carries the same values with the seed and hash as hex strings and the
+//! inputs as a compact string of `<tick delta><U|D|L|R>` pairs, e.g. `"0U4L"`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    new_seeded_game, set_direction, step, Direction, FoodStrategy, GameState, GridPos, Rules,
+};
+
+pub const REPLAY_MAGIC: [u8; 4] = *b"BSNR";
+pub const REPLAY_FORMAT_VERSION: u16 = 3;
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Most ticks a replay may run on after its last input. A snake left alone
+/// dies within a lap of the board unless the walls wrap, so anything longer
+/// is a crafted file rather than a recorded game.
+const MAX_IDLE_TICKS: u64 = 1 << 20;
+
+const RULE_WRAP_WALLS: u8 = 1;
+
+const FOOD_UNIFORM: u8 = 0;
+const FOOD_FAIR: u8 = 1;
+const FOOD_SEQUENCE: u8 = 2;
+const FOOD_EDGE_BIASED: u8 = 3;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ReplayInput {
+    pub tick: u64,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Replay {
+    pub game_version: String,
+    pub seed: u64,
+    pub rules: Rules,
+    pub grid_width: i32,
+    pub grid_height: i32,
+    pub ticks: u64,
+    pub inputs: Vec<ReplayInput>,
+    pub final_score: u32,
+    pub state_hash: u64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReplayError {
+    BadMagic,
+    UnsupportedVersion { found: u16, supported: u16 },
+    Truncated,
+    ChecksumMismatch,
+    Corrupt(&'static str),
+    Json(String),
+    Desync { tick: u64 },
+    ResultMismatch,
+    TickLimit { limit: u64 },
+    OutOfRange { value: i32 },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not a replay file"),
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "replay format version {found} is not supported (expected {supported})"
+            ),
+            Self::Truncated => write!(f, "replay file is truncated"),
+            Self::ChecksumMismatch => write!(f, "replay checksum does not match"),
+            Self::Corrupt(reason) => write!(f, "replay is corrupted: {reason}"),
+            Self::Json(reason) => write!(f, "invalid replay JSON: {reason}"),
+            Self::Desync { tick } => write!(f, "replay ended early at tick {tick}"),
+            Self::ResultMismatch => write!(f, "replay does not reproduce the recorded result"),
+            Self::TickLimit { limit } => write!(f, "replay is still running after {limit} ticks"),
+            Self::OutOfRange { value } => {
+                write!(f, "{value} does not fit in a replay's 16-bit coordinates")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Collects the inputs of a live game so it can be saved as a [`Replay`].
+#[derive(Debug, Clone)]
+pub struct ReplayRecorder {
+    seed: u64,
+    rules: Rules,
+    grid_width: i32,
+    grid_height: i32,
+    inputs: Vec<ReplayInput>,
+}
+
+impl ReplayRecorder {
+    /// Starts recording a game that has not been stepped yet.
+    pub fn new(state: &GameState) -> Self {
+        Self {
+            seed: state.seed,
+            rules: state.rules.clone(),
+            grid_width: state.grid_width,
+            grid_height: state.grid_height,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Applies `direction` to `state` and records it if it was accepted.
+    pub fn set_direction(&mut self, state: &mut GameState, direction: Direction) {
+        let before = state.pending_direction;
+        set_direction(state, direction);
+        if state.alive && state.pending_direction != before {
+            self.inputs.push(ReplayInput {
+                tick: state.tick,
+                direction,
+            });
+        }
+    }
+
+    pub fn finish(self, state: &GameState) -> Replay {
+        Replay {
+            game_version: GAME_VERSION.to_string(),
+            seed: self.seed,
+            rules: self.rules,
+            grid_width: self.grid_width,
+            grid_height: self.grid_height,
+            ticks: state.tick,
+            inputs: self.inputs,
+            final_score: state.score,
+            state_hash: state.stable_hash(),
+        }
+    }
+}
+
+impl Replay {
+    /// Re-simulates the replay and checks it reproduces the recorded result.
+    pub fn play(&self) -> Result<GameState, ReplayError> {
+        self.play_limited(u64::MAX)
+    }
+
+    /// Like [`Replay::play`], but gives up once `max_ticks` have been played.
+    pub fn play_limited(&self, max_ticks: u64) -> Result<GameState, ReplayError> {
+        let mut state = new_seeded_game(
+            self.grid_width,
+            self.grid_height,
+            self.seed,
+            self.rules.clone(),
+        );
+        let mut inputs = self.inputs.iter().peekable();
+
+        while state.tick < self.ticks {
+            if state.tick >= max_ticks {
+                return Err(ReplayError::TickLimit { limit: max_ticks });
+            }
+            while let Some(input) = inputs.next_if(|input| input.tick == state.tick) {
+                set_direction(&mut state, input.direction);
+            }
+            if !state.alive {
+                return Err(ReplayError::Desync { tick: state.tick });
+            }
+            step(&mut state);
+        }
+
+        if inputs.next().is_some()
+            || state.score != self.final_score
+            || state.stable_hash() != self.state_hash
+        {
+            return Err(ReplayError::ResultMismatch);
+        }
+        Ok(state)
+    }
+
+    /// Fails if a grid size or cell does not fit in 16 bits.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReplayError> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&REPLAY_MAGIC);
+        out.extend_from_slice(&REPLAY_FORMAT_VERSION.to_le_bytes());
+        let version =
+            &self.game_version.as_bytes()[..self.game_version.len().min(u8::MAX as usize)];
+        out.push(version.len() as u8);
+        out.extend_from_slice(version);
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.push(rules_to_flags(&self.rules));
+        write_food(&mut out, &self.rules.food)?;
+        write_cells(&mut out, &self.rules.obstacles)?;
+        write_coord(&mut out, self.grid_width)?;
+        write_coord(&mut out, self.grid_height)?;
+        write_varint(&mut out, self.ticks);
+        out.extend_from_slice(&self.final_score.to_le_bytes());
+        out.extend_from_slice(&self.state_hash.to_le_bytes());
+        write_varint(&mut out, self.inputs.len() as u64);
+        let mut last_tick = 0;
+        for input in &self.inputs {
+            let delta = input.tick - last_tick;
+            write_varint(&mut out, (delta << 2) | direction_to_bits(input.direction));
+            last_tick = input.tick;
+        }
+        let checksum = fnv1a32(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if bytes.len() < REPLAY_MAGIC.len() || bytes[..REPLAY_MAGIC.len()] != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let mut reader = Reader {
+            bytes,
+            pos: REPLAY_MAGIC.len(),
+        };
+        let version = u16::from_le_bytes(reader.array()?);
+        check_version(version)?;
+
+        if bytes.len() < reader.pos + 4 {
+            return Err(ReplayError::Truncated);
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - 4);
+        if fnv1a32(body) != u32::from_le_bytes(checksum.try_into().unwrap_or_default()) {
+            return Err(ReplayError::ChecksumMismatch);
+        }
+        reader.bytes = body;
+
+        let version_len = reader.array::<1>()?[0] as usize;
+        let game_version = std::str::from_utf8(reader.take(version_len)?)
+            .map_err(|_| ReplayError::Corrupt("game version is not UTF-8"))?
+            .to_string();
+        let seed = u64::from_le_bytes(reader.array()?);
+        let mut rules = rules_from_flags(reader.array::<1>()?[0])?;
+        rules.food = reader.food()?;
+        rules.obstacles = reader.cells()?;
+        let grid_width = u16::from_le_bytes(reader.array()?) as i32;
+        let grid_height = u16::from_le_bytes(reader.array()?) as i32;
+        let ticks = reader.varint()?;
+        let final_score = u32::from_le_bytes(reader.array()?);
+        let state_hash = u64::from_le_bytes(reader.array()?);
+
+        let count = reader.varint()?;
+        let mut inputs = Vec::new();
+        let mut tick: u64 = 0;
+        for _ in 0..count {
+            let packed = reader.varint()?;
+            tick = tick
+                .checked_add(packed >> 2)
+                .ok_or(ReplayError::Corrupt("input tick overflows"))?;
+            inputs.push(ReplayInput {
+                tick,
+                direction: direction_from_bits(packed & 0b11),
+            });
+        }
+        if reader.pos != reader.bytes.len() {
+            return Err(ReplayError::Corrupt("trailing bytes after inputs"));
+        }
+
+        let replay = Self {
+            game_version,
+            seed,
+            rules,
+            grid_width,
+            grid_height,
+            ticks,
+            inputs,
+            final_score,
+            state_hash,
+        };
+        replay.check_header()?;
+        Ok(replay)
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = JsonReplay {
+            magic: String::from_utf8_lossy(&REPLAY_MAGIC).into_owned(),
+            format_version: REPLAY_FORMAT_VERSION,
+            game_version: self.game_version.clone(),
+            seed: format!("{:016x}", self.seed),
+            rules: JsonRules {
+                wrap_walls: self.rules.wrap_walls,
+                food: self.rules.food.clone(),
+                obstacles: self.rules.obstacles.clone(),
+            },
+            grid: JsonGrid {
+                width: self.grid_width,
+                height: self.grid_height,
+            },
+            ticks: self.ticks,
+            inputs: encode_inputs(&self.inputs),
+            final_score: self.final_score,
+            state_hash: format!("{:016x}", self.state_hash),
+        };
+        serde_json::to_string_pretty(&file).unwrap_or_default()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ReplayError> {
+        let file: JsonReplay =
+            serde_json::from_str(text).map_err(|err| ReplayError::Json(err.to_string()))?;
+        if file.magic.as_bytes() != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        check_version(file.format_version)?;
+
+        let replay = Self {
+            game_version: file.game_version,
+            seed: parse_hex(&file.seed, "seed is not a hex number")?,
+            rules: Rules {
+                wrap_walls: file.rules.wrap_walls,
+                food: file.rules.food,
+                obstacles: file.rules.obstacles,
+            },
+            grid_width: file.grid.width,
+            grid_height: file.grid.height,
+            ticks: file.ticks,
+            inputs: decode_inputs(&file.inputs)?,
+            final_score: file.final_score,
+            state_hash: parse_hex(&file.state_hash, "state hash is not a hex number")?,
+        };
+        replay.check_header()?;
+        Ok(replay)
+    }
+
+    fn check_header(&self) -> Result<(), ReplayError> {
+        if self.grid_width <= 0 || self.grid_height <= 0 {
+            return Err(ReplayError::Corrupt("grid size must be positive"));
+        }
+        if self.grid_width > i32::from(u16::MAX) || self.grid_height > i32::from(u16::MAX) {
+            return Err(ReplayError::Corrupt("grid size does not fit in 16 bits"));
+        }
+        if self.inputs.iter().any(|input| input.tick > self.ticks) {
+            return Err(ReplayError::Corrupt("input recorded after the last tick"));
+        }
+        let last_input = self.inputs.last().map_or(0, |input| input.tick);
+        if self.ticks - last_input > MAX_IDLE_TICKS {
+            return Err(ReplayError::Corrupt("too many ticks after the last input"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonReplay {
+    magic: String,
+    format_version: u16,
+    game_version: String,
+    seed: String,
+    rules: JsonRules,
+    grid: JsonGrid,
+    ticks: u64,
+    inputs: String,
+    final_score: u32,
+    state_hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonRules {
+    wrap_walls: bool,
+    food: FoodStrategy,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    obstacles: Vec<GridPos>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonGrid {
+    width: i32,
+    height: i32,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ReplayError> {
+        let end = self.pos.checked_add(len).ok_or(ReplayError::Truncated)?;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(ReplayError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn varint(&mut self) -> Result<u64, ReplayError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.array::<1>()?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::Corrupt("varint is too long"))
+    }
+
+    fn food(&mut self) -> Result<FoodStrategy, ReplayError> {
+        match self.array::<1>()?[0] {
+            FOOD_UNIFORM => Ok(FoodStrategy::Uniform),
+            FOOD_FAIR => Ok(FoodStrategy::Fair {
+                min_distance: i32::from_le_bytes(self.array()?),
+            }),
+            FOOD_SEQUENCE => Ok(FoodStrategy::Sequence(self.cells()?)),
+            FOOD_EDGE_BIASED => Ok(FoodStrategy::EdgeBiased),
+            _ => Err(ReplayError::Corrupt("unknown food strategy")),
+        }
+    }
+
+    fn cells(&mut self) -> Result<Vec<GridPos>, ReplayError> {
+        let count = self.varint()?;
+        let mut cells = Vec::new();
+        for _ in 0..count {
+            cells.push(GridPos {
+                x: u16::from_le_bytes(self.array()?) as i32,
+                y: u16::from_le_bytes(self.array()?) as i32,
+            });
+        }
+        Ok(cells)
+    }
+}
+
+fn check_version(found: u16) -> Result<(), ReplayError> {
+    if found != REPLAY_FORMAT_VERSION {
+        return Err(ReplayError::UnsupportedVersion {
+            found,
+            supported: REPLAY_FORMAT_VERSION,
+        });
+    }
+    Ok(())
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn rules_to_flags(rules: &Rules) -> u8 {
+    if rules.wrap_walls {
+        RULE_WRAP_WALLS
+    } else {
+        0
+    }
+}
+
+fn rules_from_flags(flags: u8) -> Result<Rules, ReplayError> {
+    if flags & !RULE_WRAP_WALLS != 0 {
+        return Err(ReplayError::Corrupt("unknown rule flags"));
+    }
+    Ok(Rules {
+        wrap_walls: flags & RULE_WRAP_WALLS != 0,
+        ..Rules::default()
+    })
+}
+
+fn write_food(out: &mut Vec<u8>, food: &FoodStrategy) -> Result<(), ReplayError> {
+    match food {
+        FoodStrategy::Uniform => out.push(FOOD_UNIFORM),
+        FoodStrategy::Fair { min_distance } => {
+            out.push(FOOD_FAIR);
+            out.extend_from_slice(&min_distance.to_le_bytes());
+        }
+        FoodStrategy::Sequence(cells) => {
+            out.push(FOOD_SEQUENCE);
+            write_cells(out, cells)?;
+        }
+        FoodStrategy::EdgeBiased => out.push(FOOD_EDGE_BIASED),
+    }
+    Ok(())
+}
+
+fn write_cells(out: &mut Vec<u8>, cells: &[GridPos]) -> Result<(), ReplayError> {
+    write_varint(out, cells.len() as u64);
+    for cell in cells {
+        write_coord(out, cell.x)?;
+        write_coord(out, cell.y)?;
+    }
+    Ok(())
+}
+
+fn write_coord(out: &mut Vec<u8>, value: i32) -> Result<(), ReplayError> {
+    let value = u16::try_from(value).map_err(|_| ReplayError::OutOfRange { value })?;
+    out.extend_from_slice(&value.to_le_bytes());
+    Ok(())
+}
+
+fn direction_to_bits(direction: Direction) -> u64 {
+    match direction {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+fn direction_from_bits(bits: u64) -> Direction {
+    match bits {
+        0 => Direction::Up,
+        1 => Direction::Down,
+        2 => Direction::Left,
+        _ => Direction::Right,
+    }
+}
+
+fn direction_letter(direction: Direction) -> char {
+    match direction {
+        Direction::Up => 'U',
+        Direction::Down => 'D',
+        Direction::Left => 'L',
+        Direction::Right => 'R',
+    }
+}
+
+fn encode_inputs(inputs: &[ReplayInput]) -> String {
+    let mut out = String::new();
+    let mut last_tick = 0;
+    for input in inputs {
+        out.push_str(&(input.tick - last_tick).to_string());
+        out.push(direction_letter(input.direction));
+        last_tick = input.tick;
+    }
+    out
+}
+
+fn decode_inputs(text: &str) -> Result<Vec<ReplayInput>, ReplayError> {
+    let mut inputs = Vec::new();
+    let mut tick: u64 = 0;
+    let mut digits = String::new();
+    for ch in text.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let direction = match ch {
+            'U' => Direction::Up,
+            'D' => Direction::Down,
+            'L' => Direction::Left,
+            'R' => Direction::Right,
+            _ => return Err(ReplayError::Corrupt("unknown input direction")),
+        };
+        let delta: u64 = digits
+            .parse()
+            .map_err(|_| ReplayError::Corrupt("input is missing its tick delta"))?;
+        tick = tick
+            .checked_add(delta)
+            .ok_or(ReplayError::Corrupt("input tick overflows"))?;
+        inputs.push(ReplayInput { tick, direction });
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(ReplayError::Corrupt("input is missing its direction"));
+    }
+    Ok(inputs)
+}
+
+fn parse_hex(text: &str, reason: &'static str) -> Result<u64, ReplayError> {
+    u64::from_str_radix(text, 16).map_err(|_| ReplayError::Corrupt(reason))
+}
+
+fn fnv1a32(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded_replay() -> Replay {
+        let rules = Rules {
+            food: FoodStrategy::Sequence(vec![GridPos { x: 3, y: 3 }, GridPos { x: 8, y: 1 }]),
+            obstacles: vec![GridPos { x: 10, y: 7 }],
+            ..Rules::default()
+        };
+        let mut state = new_seeded_game(12, 9, 99, rules);
+        let mut recorder = ReplayRecorder::new(&state);
+        let turns = [
+            (2, Direction::Up),
+            (5, Direction::Left),
+            (9, Direction::Down),
+        ];
+        for _ in 0..14 {
+            if let Some((_, direction)) = turns.iter().find(|(tick, _)| *tick == state.tick) {
+                recorder.set_direction(&mut state, *direction);
+            }
+            step(&mut state);
+        }
+        recorder.finish(&state)
+    }
+
+    #[test]
+    fn binary_round_trip_reproduces_the_game() {
+        let replay = recorded_replay();
+
+        let bytes = replay.to_bytes().expect("encodable replay");
+        let decoded = Replay::from_bytes(&bytes).expect("valid replay");
+
+        assert_eq!(decoded, replay);
+        assert_eq!(
+            decoded.play().map(|state| state.score),
+            Ok(replay.final_score)
+        );
+    }
+
+    #[test]
+    fn json_round_trip_preserves_every_field() {
+        let replay = recorded_replay();
+
+        let decoded = Replay::from_json(&replay.to_json()).expect("valid replay");
+
+        assert_eq!(decoded, replay);
+    }
+
+    #[test]
+    fn corrupted_or_foreign_files_are_rejected() {
+        let bytes = recorded_replay().to_bytes().expect("encodable replay");
+
+        let mut flipped = bytes.clone();
+        flipped[20] ^= 0xff;
+        assert_eq!(
+            Replay::from_bytes(&flipped),
+            Err(ReplayError::ChecksumMismatch)
+        );
+        assert_eq!(
+            Replay::from_bytes(&bytes[..10]),
+            Err(ReplayError::ChecksumMismatch)
+        );
+        assert_eq!(Replay::from_bytes(b"PNG"), Err(ReplayError::BadMagic));
+
+        let mut future = bytes.clone();
+        future[4] = 9;
+        assert_eq!(
+            Replay::from_bytes(&future),
+            Err(ReplayError::UnsupportedVersion {
+                found: 9,
+                supported: REPLAY_FORMAT_VERSION
+            })
+        );
+    }
+
+    #[test]
+    fn tampered_result_fails_verification() {
+        let mut replay = recorded_replay();
+        replay.final_score += 1;
+
+        assert_eq!(replay.play().err(), Some(ReplayError::ResultMismatch));
+    }
+
+    #[test]
+    fn endless_replays_are_rejected_or_cut_short() {
+        let mut replay = recorded_replay();
+        replay.ticks += MAX_IDLE_TICKS;
+        assert_eq!(
+            Replay::from_json(&replay.to_json()),
+            Err(ReplayError::Corrupt("too many ticks after the last input"))
+        );
+
+        let replay = recorded_replay();
+        assert_eq!(
+            replay.play_limited(5).err(),
+            Some(ReplayError::TickLimit { limit: 5 })
+        );
+    }
+
+    #[test]
+    fn cells_outside_16_bits_are_not_encoded() {
+        let mut replay = recorded_replay();
+        replay.rules.obstacles.push(GridPos { x: -1, y: 0 });
+        assert_eq!(
+            replay.to_bytes(),
+            Err(ReplayError::OutOfRange { value: -1 })
+        );
+
+        let mut replay = recorded_replay();
+        replay.grid_width = 70_000;
+        assert_eq!(
+            replay.to_bytes(),
+            Err(ReplayError::OutOfRange { value: 70_000 })
+        );
+    }
+}
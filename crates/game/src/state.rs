@@ -1,4 +1,9 @@
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::food::FoodStrategy;
+use crate::rng::GameRng;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -17,16 +22,29 @@ impl Direction {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct GridPos {
     pub x: i32,
     pub y: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct Rules {
+    pub wrap_walls: bool,
+    pub food: FoodStrategy,
+    /// Cells that kill the snake on contact, e.g. the walls of a level.
+    #[serde(default)]
+    pub obstacles: Vec<GridPos>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub grid_width: i32,
     pub grid_height: i32,
+    pub seed: u64,
+    pub rules: Rules,
+    pub rng: GameRng,
+    pub tick: u64,
     pub snake: Vec<GridPos>,
     pub direction: Direction,
     pub pending_direction: Direction,
@@ -34,3 +52,9 @@ pub struct GameState {
     pub score: u32,
     pub alive: bool,
 }
+
+impl GameState {
+    pub fn is_obstacle(&self, pos: GridPos) -> bool {
+        self.rules.obstacles.contains(&pos)
+    }
+}
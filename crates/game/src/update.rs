@@ -1,6 +1,8 @@
-use rand::Rng;
+use rand::SeedableRng;
 
-use crate::{Direction, GameState, GridPos};
+use crate::food::FoodSpawner;
+use crate::rng::GameRng;
+use crate::{Direction, GameState, GridPos, Rules};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum StepResult {
@@ -10,13 +12,18 @@ pub enum StepResult {
 }
 
 pub fn new_game(grid_width: i32, grid_height: i32) -> GameState {
-    let start = GridPos {
-        x: grid_width / 2,
-        y: grid_height / 2,
-    };
+    new_seeded_game(grid_width, grid_height, rand::random(), Rules::default())
+}
+
+pub fn new_seeded_game(grid_width: i32, grid_height: i32, seed: u64, rules: Rules) -> GameState {
+    let start = start_position(grid_width, grid_height, &rules.obstacles);
     let mut state = GameState {
         grid_width,
         grid_height,
+        seed,
+        rules,
+        rng: GameRng::seed_from_u64(seed),
+        tick: 0,
         snake: vec![start],
         direction: Direction::Right,
         pending_direction: Direction::Right,
@@ -25,33 +32,60 @@ pub fn new_game(grid_width: i32, grid_height: i32) -> GameState {
         alive: true,
     };
 
-    state.food = spawn_food(&state);
+    let strategy = state.rules.food.clone();
+    state.food = spawn_food(&mut state, &strategy);
     state
 }
 
 pub fn set_direction(state: &mut GameState, direction: Direction) {
-    if direction != state.pending_direction.opposite() {
+    if direction != state.direction.opposite() {
         state.pending_direction = direction;
     }
 }
 
+/// The cell the head of a dead snake ran into, off the board if it was a
+/// wall.
+pub fn crash_cell(state: &GameState) -> Option<GridPos> {
+    if state.alive {
+        return None;
+    }
+    state.snake.first().map(|head| ahead_of(state, *head))
+}
+
 pub fn step(state: &mut GameState) -> StepResult {
+    let result = advance(state);
+
+    #[cfg(any(debug_assertions, feature = "check-invariants"))]
+    if let Err(err) = state.validate() {
+        panic!("game state invariant violated at tick {}: {err}", state.tick);
+    }
+
+    result
+}
+
+fn advance(state: &mut GameState) -> StepResult {
     if !state.alive {
         return StepResult::GameOver;
     }
 
+    state.tick += 1;
     state.direction = state.pending_direction;
     let head = match state.snake.first() {
         Some(head) => *head,
         None => return StepResult::GameOver,
     };
 
-    let next = next_position(head, state.direction);
+    let next = ahead_of(state, head);
     if next.x < 0 || next.x >= state.grid_width || next.y < 0 || next.y >= state.grid_height {
         state.alive = false;
         return StepResult::GameOver;
     }
 
+    if state.is_obstacle(next) {
+        state.alive = false;
+        return StepResult::GameOver;
+    }
+
     let tail = state.snake.last().copied();
     let hits_body = state.snake.contains(&next);
     let is_food = next == state.food;
@@ -63,7 +97,8 @@ pub fn step(state: &mut GameState) -> StepResult {
     state.snake.insert(0, next);
     if is_food {
         state.score = state.score.saturating_add(1);
-        state.food = spawn_food(state);
+        let strategy = state.rules.food.clone();
+        state.food = spawn_food(state, &strategy);
         StepResult::Ate
     } else {
         state.snake.pop();
@@ -71,6 +106,29 @@ pub fn step(state: &mut GameState) -> StepResult {
     }
 }
 
+/// The centre cell, or the open cell nearest to it when an obstacle is there.
+fn start_position(grid_width: i32, grid_height: i32, obstacles: &[GridPos]) -> GridPos {
+    let centre = GridPos {
+        x: grid_width / 2,
+        y: grid_height / 2,
+    };
+    (0..grid_height)
+        .flat_map(|y| (0..grid_width).map(move |x| GridPos { x, y }))
+        .filter(|cell| !obstacles.contains(cell))
+        .min_by_key(|cell| ((cell.x - centre.x).abs() + (cell.y - centre.y).abs(), cell.y, cell.x))
+        .unwrap_or(centre)
+}
+
+/// The cell in front of `head`, wrapped onto the board when the walls wrap.
+fn ahead_of(state: &GameState, head: GridPos) -> GridPos {
+    let mut next = next_position(head, state.direction);
+    if state.rules.wrap_walls {
+        next.x = next.x.rem_euclid(state.grid_width);
+        next.y = next.y.rem_euclid(state.grid_height);
+    }
+    next
+}
+
 fn next_position(head: GridPos, direction: Direction) -> GridPos {
     match direction {
         Direction::Up => GridPos {
@@ -92,27 +150,17 @@ fn next_position(head: GridPos, direction: Direction) -> GridPos {
     }
 }
 
-fn spawn_food(state: &GameState) -> GridPos {
-    let mut rng = rand::thread_rng();
-    let mut candidate = GridPos { x: 0, y: 0 };
-
-    for _ in 0..100 {
-        candidate = GridPos {
-            x: rng.gen_range(0..state.grid_width),
-            y: rng.gen_range(0..state.grid_height),
-        };
-
-        if !state.snake.contains(&candidate) {
-            return candidate;
-        }
-    }
-
-    candidate
+fn spawn_food(state: &mut GameState, spawner: &(impl FoodSpawner + ?Sized)) -> GridPos {
+    let mut rng = state.rng;
+    let food = spawner.spawn(state, &mut rng);
+    state.rng = rng;
+    food.unwrap_or(state.food)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::FoodStrategy;
 
     #[test]
     fn step_moves_snake_and_increments_score_on_food() {
@@ -140,6 +188,15 @@ mod tests {
         assert_eq!(state.direction, Direction::Right);
     }
 
+    #[test]
+    fn quick_turns_cannot_reverse_into_body() {
+        let mut state = new_game(10, 10);
+        set_direction(&mut state, Direction::Up);
+        set_direction(&mut state, Direction::Left);
+
+        assert_eq!(state.pending_direction, Direction::Up);
+    }
+
     #[test]
     fn step_allows_moving_into_tail_when_not_growing() {
         let mut state = new_game(10, 10);
@@ -156,4 +213,75 @@ mod tests {
         assert_eq!(result, StepResult::Moved);
         assert!(state.alive);
     }
+
+    #[test]
+    fn step_wraps_around_walls_when_enabled() {
+        let rules = Rules {
+            wrap_walls: true,
+            ..Rules::default()
+        };
+        let mut state = new_seeded_game(10, 10, 7, rules);
+        state.snake = vec![GridPos { x: 9, y: 4 }];
+        state.food = GridPos { x: 0, y: 0 };
+
+        let result = step(&mut state);
+
+        assert_eq!(result, StepResult::Moved);
+        assert_eq!(state.snake[0], GridPos { x: 0, y: 4 });
+    }
+
+    #[test]
+    fn crash_cell_is_where_the_head_ran_into() {
+        let mut state = new_game(10, 10);
+        assert_eq!(crash_cell(&state), None);
+        state.snake = vec![GridPos { x: 9, y: 3 }];
+        state.food = GridPos { x: 0, y: 0 };
+
+        assert_eq!(step(&mut state), StepResult::GameOver);
+        assert_eq!(crash_cell(&state), Some(GridPos { x: 10, y: 3 }));
+    }
+
+    #[test]
+    fn obstacles_end_the_game_and_move_the_start() {
+        let centre = GridPos { x: 5, y: 5 };
+        let rules = Rules {
+            obstacles: vec![centre, GridPos { x: 6, y: 4 }],
+            ..Rules::default()
+        };
+        let mut state = new_seeded_game(10, 10, 7, rules);
+        assert_eq!(state.snake[0], GridPos { x: 5, y: 4 });
+
+        let result = step(&mut state);
+
+        assert_eq!(result, StepResult::GameOver);
+        assert!(!state.alive);
+    }
+
+    #[test]
+    fn seeded_games_place_identical_food() {
+        let first = new_seeded_game(10, 10, 42, Rules::default());
+        let second = new_seeded_game(10, 10, 42, Rules::default());
+
+        assert_eq!(first.food, second.food);
+        assert_eq!(first.rng, second.rng);
+    }
+
+    #[test]
+    fn step_uses_food_strategy_from_rules() {
+        let target = GridPos { x: 0, y: 0 };
+        let rules = Rules {
+            food: FoodStrategy::Sequence(vec![GridPos { x: 9, y: 9 }, target]),
+            ..Rules::default()
+        };
+        let mut state = new_seeded_game(10, 10, 3, rules);
+        let head = state.snake[0];
+        state.food = GridPos {
+            x: head.x + 1,
+            y: head.y,
+        };
+
+        step(&mut state);
+
+        assert_eq!(state.food, target);
+    }
 }
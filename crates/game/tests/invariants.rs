@@ -0,0 +1,114 @@
+use game::{
+    new_seeded_game, step, Direction, FoodStrategy, GameState, GridPos, Replay, ReplayRecorder,
+    Rules,
+};
+use proptest::prelude::*;
+
+fn direction() -> impl Strategy<Value = Direction> {
+    prop_oneof![
+        Just(Direction::Up),
+        Just(Direction::Down),
+        Just(Direction::Left),
+        Just(Direction::Right),
+    ]
+}
+
+fn food_strategy() -> impl Strategy<Value = FoodStrategy> {
+    prop_oneof![
+        Just(FoodStrategy::Uniform),
+        (0..12i32).prop_map(|min_distance| FoodStrategy::Fair { min_distance }),
+        prop::collection::vec((0..16i32, 0..12i32), 0..6).prop_map(|cells| {
+            FoodStrategy::Sequence(cells.into_iter().map(|(x, y)| GridPos { x, y }).collect())
+        }),
+        Just(FoodStrategy::EdgeBiased),
+    ]
+}
+
+fn obstacles() -> impl Strategy<Value = Vec<GridPos>> {
+    prop::collection::hash_set((0..16i32, 0..12i32), 0..12)
+        .prop_map(|cells| cells.into_iter().map(|(x, y)| GridPos { x, y }).collect())
+}
+
+fn rules() -> impl Strategy<Value = Rules> {
+    (any::<bool>(), food_strategy(), obstacles()).prop_map(|(wrap_walls, food, obstacles)| Rules {
+        wrap_walls,
+        food,
+        obstacles,
+    })
+}
+
+fn play(
+    width: i32,
+    height: i32,
+    seed: u64,
+    rules: Rules,
+    inputs: &[Option<Direction>],
+) -> (GameState, Replay) {
+    let mut state = new_seeded_game(width, height, seed, rules);
+    let mut recorder = ReplayRecorder::new(&state);
+    for input in inputs {
+        if let Some(direction) = input {
+            recorder.set_direction(&mut state, *direction);
+        }
+        step(&mut state);
+        if !state.alive {
+            break;
+        }
+    }
+    let replay = recorder.finish(&state);
+    (state, replay)
+}
+
+proptest! {
+    #[test]
+    fn random_inputs_keep_state_valid(
+        width in 1..24i32,
+        height in 1..24i32,
+        seed in any::<u64>(),
+        mut rules in rules(),
+        inputs in prop::collection::vec(prop::option::of(direction()), 0..400),
+    ) {
+        // Leave the centre open so the grid always has a starting cell.
+        let centre = GridPos { x: width / 2, y: height / 2 };
+        rules.obstacles.retain(|cell| *cell != centre);
+        let mut state = new_seeded_game(width, height, seed, rules);
+        prop_assert_eq!(state.validate(), Ok(()));
+
+        for input in inputs {
+            if let Some(direction) = input {
+                game::set_direction(&mut state, direction);
+            }
+            let before = state.snake.len();
+            step(&mut state);
+            prop_assert_eq!(state.validate(), Ok(()));
+            prop_assert!(state.snake.len() == before || state.snake.len() == before + 1);
+        }
+    }
+
+    #[test]
+    fn identical_inputs_produce_identical_hashes(
+        seed in any::<u64>(),
+        rules in rules(),
+        inputs in prop::collection::vec(prop::option::of(direction()), 0..200),
+    ) {
+        let (first, _) = play(16, 12, seed, rules.clone(), &inputs);
+        let (second, _) = play(16, 12, seed, rules, &inputs);
+
+        prop_assert_eq!(first.stable_hash(), second.stable_hash());
+    }
+
+    #[test]
+    fn recorded_replays_reproduce_the_game(
+        seed in any::<u64>(),
+        rules in rules(),
+        inputs in prop::collection::vec(prop::option::of(direction()), 0..200),
+    ) {
+        let (state, replay) = play(16, 12, seed, rules, &inputs);
+
+        let decoded = Replay::from_bytes(&replay.to_bytes().expect("encodable replay"))
+            .expect("valid replay");
+        let replayed = decoded.play().expect("replay reproduces the game");
+
+        prop_assert_eq!(replayed.stable_hash(), state.stable_hash());
+    }
+}